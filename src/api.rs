@@ -0,0 +1,349 @@
+//! `LobApi`, the Lob endpoints as a trait, so that code sending mail can be tested against
+//! `fake::FakeLob` instead of a `Client`.
+
+use crate::client::Client;
+use crate::error::Error;
+use crate::fake::{FakeLob, Replies};
+use crate::id::*;
+use crate::model::*;
+use crate::stream::{self, ListStream};
+use async_trait::async_trait;
+
+// Each method is followed by the name of the `FakeLob` method that configures its replies
+macro_rules! lob_api {
+    ($(fn $name:ident($($arg:ident: $ty:ty),* $(,)?) -> $ret:ty => $on:ident;)*) => {
+        /// Every Lob endpoint, implemented by `Client` and `fake::FakeLob`. Each method behaves as
+        /// the `Client` method of the same name, except that the few generic ones take the type
+        /// their arguments are converted into, e.g. `verify_us_address` takes an
+        /// `AddressVerificationInput`.
+        ///
+        /// The trait is object safe, so code under test can take an `Arc<dyn LobApi>`.
+        #[async_trait]
+        pub trait LobApi: Send + Sync {
+            $(
+                async fn $name(&self, $($arg: $ty),*) -> Result<$ret, Error>;
+            )*
+
+            // The `Client` methods of the same names delegate to these
+            fn list_addresses_stream(
+                &self,
+                options: Option<ListAddressesOptions>,
+            ) -> ListStream<'_, Address> {
+                stream::paginate(options.unwrap_or_default(), move |options| {
+                    self.list_addresses(Some(options))
+                })
+            }
+
+            fn list_postcards_stream(
+                &self,
+                options: Option<ListPostcardOptions>,
+            ) -> ListStream<'_, Postcard> {
+                stream::paginate(options.unwrap_or_default(), move |options| {
+                    self.list_postcards(Some(options))
+                })
+            }
+
+            fn list_letters_stream(
+                &self,
+                options: Option<ListLetterOptions>,
+            ) -> ListStream<'_, Letter> {
+                stream::paginate(options.unwrap_or_default(), move |options| {
+                    self.list_letters(Some(options))
+                })
+            }
+
+            fn list_self_mailers_stream(
+                &self,
+                options: Option<ListSelfMailerOptions>,
+            ) -> ListStream<'_, SelfMailer> {
+                stream::paginate(options.unwrap_or_default(), move |options| {
+                    self.list_self_mailers(Some(options))
+                })
+            }
+
+            fn list_checks_stream(
+                &self,
+                options: Option<ListCheckOptions>,
+            ) -> ListStream<'_, Check> {
+                stream::paginate(options.unwrap_or_default(), move |options| {
+                    self.list_checks(Some(options))
+                })
+            }
+
+            fn list_bank_accounts_stream(
+                &self,
+                options: Option<ListBankAccountOptions>,
+            ) -> ListStream<'_, BankAccount> {
+                stream::paginate(options.unwrap_or_default(), move |options| {
+                    self.list_bank_accounts(Some(options))
+                })
+            }
+
+            fn list_templates_stream(
+                &self,
+                options: Option<ListTemplateOptions>,
+            ) -> ListStream<'_, Template> {
+                stream::paginate(options.unwrap_or_default(), move |options| {
+                    self.list_templates(Some(options))
+                })
+            }
+
+            fn list_template_versions_stream<'a>(
+                &'a self,
+                template_id: &'a TemplateId,
+                options: Option<ListTemplateVersionOptions>,
+            ) -> ListStream<'a, TemplateVersion> {
+                stream::paginate(options.unwrap_or_default(), move |options| {
+                    self.list_template_versions(template_id, Some(options))
+                })
+            }
+
+            fn list_campaigns_stream(
+                &self,
+                options: Option<ListCampaignOptions>,
+            ) -> ListStream<'_, Campaign> {
+                stream::paginate(options.unwrap_or_default(), move |options| {
+                    self.list_campaigns(Some(options))
+                })
+            }
+
+            fn list_events_stream(
+                &self,
+                options: Option<ListEventOptions>,
+            ) -> ListStream<'_, Event> {
+                stream::paginate(options.unwrap_or_default(), move |options| {
+                    self.list_events(Some(options))
+                })
+            }
+        }
+
+        #[async_trait]
+        impl LobApi for Client {
+            $(
+                async fn $name(&self, $($arg: $ty),*) -> Result<$ret, Error> {
+                    Client::$name(self, $($arg),*).await
+                }
+            )*
+        }
+
+        impl FakeLob {
+            $(
+                #[doc = concat!("The replies to `", stringify!($name), "`.")]
+                pub fn $on(&self) -> Replies<'_, $ret> {
+                    Replies::new(self, stringify!($name))
+                }
+            )*
+        }
+
+        #[async_trait]
+        impl LobApi for FakeLob {
+            $(
+                async fn $name(&self, $($arg: $ty),*) -> Result<$ret, Error> {
+                    let mut args = serde_json::Map::new();
+                    $(
+                        args.insert(
+                            stringify!($arg).to_owned(),
+                            serde_json::to_value(&$arg).expect("arguments serialize to JSON"),
+                        );
+                    )*
+                    self.call(stringify!($name), args.into())
+                }
+            )*
+        }
+    };
+}
+
+lob_api! {
+    fn create_address(address: NewAddress) -> Address => on_create_address;
+    fn get_address(id: &AddressId) -> Address => on_get_address;
+    fn delete_address(id: &AddressId) -> Delete => on_delete_address;
+    fn list_addresses(
+        options: Option<ListAddressesOptions>,
+    ) -> ListResponse<Address> => on_list_addresses;
+    fn verify_us_address(
+        address: AddressVerificationInput,
+        options: Option<VerifyAddressOptions>,
+    ) -> UsVerification => on_verify_us_address;
+    fn bulk_verify_us_addresses(
+        addresses: Vec<AddressVerificationInput>,
+        options: Option<VerifyAddressOptions>,
+    ) -> Vec<Result<UsVerification, LobError>> => on_bulk_verify_us_addresses;
+    fn autocomplete_address(
+        address_prefix: String,
+        options: Option<AutocompleteAddressOptions>,
+    ) -> UsAutocompletion => on_autocomplete_address;
+    fn us_zip_lookup(zip_code: String) -> UsZipLookup => on_us_zip_lookup;
+    fn verify_intl_address(
+        address: &InternationalVerificationInput,
+    ) -> InternationalVerification => on_verify_intl_address;
+    fn bulk_verify_intl_addresses(
+        addresses: &[InternationalVerificationInput],
+    ) -> Vec<Result<InternationalVerification, LobError>> => on_bulk_verify_intl_addresses;
+
+    fn create_postcard(postcard: NewPostcard) -> Postcard => on_create_postcard;
+    fn create_postcard_with_options(
+        postcard: NewPostcard,
+        options: RequestOptions,
+    ) -> Postcard => on_create_postcard_with_options;
+    fn get_postcard(postcard_id: &PostcardId) -> Postcard => on_get_postcard;
+    fn cancel_postcard(postcard_id: &PostcardId) -> Delete => on_cancel_postcard;
+    fn list_postcards(
+        options: Option<ListPostcardOptions>,
+    ) -> ListResponse<Postcard> => on_list_postcards;
+
+    fn create_letter(letter: NewLetter) -> Letter => on_create_letter;
+    fn create_letter_with_options(
+        letter: NewLetter,
+        options: RequestOptions,
+    ) -> Letter => on_create_letter_with_options;
+    fn get_letter(letter_id: &LetterId) -> Letter => on_get_letter;
+    fn cancel_letter(letter_id: &LetterId) -> Delete => on_cancel_letter;
+    fn list_letters(options: Option<ListLetterOptions>) -> ListResponse<Letter> => on_list_letters;
+
+    fn create_self_mailer(self_mailer: NewSelfMailer) -> SelfMailer => on_create_self_mailer;
+    fn create_self_mailer_with_options(
+        self_mailer: NewSelfMailer,
+        options: RequestOptions,
+    ) -> SelfMailer => on_create_self_mailer_with_options;
+    fn get_self_mailer(self_mailer_id: &SelfMailerId) -> SelfMailer => on_get_self_mailer;
+    fn cancel_self_mailer(self_mailer_id: &SelfMailerId) -> Delete => on_cancel_self_mailer;
+    fn list_self_mailers(
+        options: Option<ListSelfMailerOptions>,
+    ) -> ListResponse<SelfMailer> => on_list_self_mailers;
+
+    fn create_check(check: NewCheck) -> Check => on_create_check;
+    fn create_check_with_options(
+        check: NewCheck,
+        options: RequestOptions,
+    ) -> Check => on_create_check_with_options;
+    fn get_check(check_id: &CheckId) -> Check => on_get_check;
+    fn cancel_check(check_id: &CheckId) -> Delete => on_cancel_check;
+    fn list_checks(options: Option<ListCheckOptions>) -> ListResponse<Check> => on_list_checks;
+
+    fn create_bank_account(bank_account: &NewBankAccount) -> BankAccount => on_create_bank_account;
+    fn get_bank_account(bank_account_id: &BankAccountId) -> BankAccount => on_get_bank_account;
+    fn delete_bank_account(bank_account_id: &BankAccountId) -> Delete => on_delete_bank_account;
+    fn verify_bank_account(
+        bank_account_id: &BankAccountId,
+        amounts: [u32; 2],
+    ) -> BankAccount => on_verify_bank_account;
+    fn list_bank_accounts(
+        options: Option<ListBankAccountOptions>,
+    ) -> ListResponse<BankAccount> => on_list_bank_accounts;
+
+    fn create_template(template: &NewTemplate) -> Template => on_create_template;
+    fn get_template(template_id: &TemplateId) -> Template => on_get_template;
+    fn update_template(
+        template_id: &TemplateId,
+        update: &UpdateTemplate,
+    ) -> Template => on_update_template;
+    fn delete_template(template_id: &TemplateId) -> Delete => on_delete_template;
+    fn list_templates(
+        options: Option<ListTemplateOptions>,
+    ) -> ListResponse<Template> => on_list_templates;
+    fn publish_template_version(
+        template_id: &TemplateId,
+        version_id: &TemplateVersionId,
+    ) -> Template => on_publish_template_version;
+    fn create_template_version(
+        template_id: &TemplateId,
+        version: &NewTemplateVersion,
+    ) -> TemplateVersion => on_create_template_version;
+    fn get_template_version(
+        template_id: &TemplateId,
+        version_id: &TemplateVersionId,
+    ) -> TemplateVersion => on_get_template_version;
+    fn update_template_version(
+        template_id: &TemplateId,
+        version_id: &TemplateVersionId,
+        update: &UpdateTemplateVersion,
+    ) -> TemplateVersion => on_update_template_version;
+    fn delete_template_version(
+        template_id: &TemplateId,
+        version_id: &TemplateVersionId,
+    ) -> Delete => on_delete_template_version;
+    fn list_template_versions(
+        template_id: &TemplateId,
+        options: Option<ListTemplateVersionOptions>,
+    ) -> ListResponse<TemplateVersion> => on_list_template_versions;
+
+    fn create_campaign(campaign: &NewCampaign) -> Campaign => on_create_campaign;
+    fn get_campaign(campaign_id: &CampaignId) -> Campaign => on_get_campaign;
+    fn update_campaign(
+        campaign_id: &CampaignId,
+        update: &UpdateCampaign,
+    ) -> Campaign => on_update_campaign;
+    fn delete_campaign(campaign_id: &CampaignId) -> Delete => on_delete_campaign;
+    fn list_campaigns(
+        options: Option<ListCampaignOptions>,
+    ) -> ListResponse<Campaign> => on_list_campaigns;
+    fn send_campaign(campaign_id: &CampaignId) -> Campaign => on_send_campaign;
+
+    fn create_creative(creative: &NewCreative) -> Creative => on_create_creative;
+    fn get_creative(creative_id: &CreativeId) -> Creative => on_get_creative;
+    fn update_creative(
+        creative_id: &CreativeId,
+        update: &UpdateCreative,
+    ) -> Creative => on_update_creative;
+
+    fn create_upload(upload: &NewUpload) -> Upload => on_create_upload;
+    fn get_upload(upload_id: &UploadId) -> Upload => on_get_upload;
+    fn list_uploads(campaign_id: Option<&CampaignId>) -> Vec<Upload> => on_list_uploads;
+    fn upload_file(
+        upload_id: &UploadId,
+        filename: String,
+        data: Vec<u8>,
+    ) -> UploadedFile => on_upload_file;
+    fn create_upload_export(
+        upload_id: &UploadId,
+        export_type: ExportType,
+    ) -> NewUploadExport => on_create_upload_export;
+    fn get_upload_export(
+        upload_id: &UploadId,
+        export_id: &UploadExportId,
+    ) -> UploadExport => on_get_upload_export;
+
+    fn get_event(event_id: &EventId) -> Event => on_get_event;
+    fn list_events(options: Option<ListEventOptions>) -> ListResponse<Event> => on_list_events;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockLob;
+    use futures::TryStreamExt;
+    use std::sync::Arc;
+    use tokio_test::block_on;
+
+    #[test]
+    fn client_implements_lob_api() {
+        block_on(async {
+            let mock = MockLob::start().await;
+            let lob: Arc<dyn LobApi> = Arc::new(mock.client());
+            let address = lob
+                .create_address(NewAddress {
+                    description: None,
+                    name: Some("Wyyerd Central".to_owned()),
+                    company: None,
+                    phone: None,
+                    email: None,
+                    address_line1: "5600 Arapahoe Ave. STE 200".to_owned(),
+                    address_line2: None,
+                    address_city: Some("Boulder".to_owned()),
+                    address_state: Some("CO".to_owned()),
+                    address_zip: Some("80304".to_owned()),
+                    address_country: Some("US".to_owned()),
+                    metadata: None,
+                })
+                .await
+                .unwrap();
+            let addresses: Vec<Address> =
+                lob.list_addresses_stream(None).try_collect().await.unwrap();
+            assert_eq!(addresses, vec![address.clone()]);
+            assert_eq!(
+                lob.delete_address(&address.id).await.unwrap().id,
+                address.id.as_str()
+            );
+        })
+    }
+}
@@ -0,0 +1,398 @@
+//! Builders for the mail pieces with the most optional fields. Each is started with the fields
+//! Lob requires, e.g. `NewLetter::builder(to, from, file)`, so a piece missing one does not
+//! compile.
+//!
+//! The bottom of a check is either a `message` or `check_bottom` artwork, never both. Which one
+//! was chosen is part of the type of a `NewCheckBuilder`, and only a builder that has chosen one
+//! can `build`:
+//!
+//! ```
+//! use lob::model::{NewCheck, SendAddressComponents};
+//!
+//! let to = SendAddressComponents {
+//!     name: "Jared Polis".to_owned(),
+//!     address_line1: "200 E Colfax Ave".to_owned(),
+//!     address_line2: None,
+//!     address_city: "Denver".to_owned(),
+//!     address_state: "CO".to_owned(),
+//!     address_zip: "80203".to_owned(),
+//!     address_country: None,
+//! };
+//! let check = NewCheck::builder(
+//!     to,
+//!     "adr_d3489cd64c791ab5".parse::<lob::id::AddressId>().unwrap(),
+//!     "bank_8cad8df5354d33f".parse().unwrap(),
+//!     12.50,
+//! )
+//! .memo("rebate")
+//! .message("Thanks for your business!")
+//! .build();
+//! assert_eq!(check.message.as_deref(), Some("Thanks for your business!"));
+//! ```
+//!
+//! Neither a check without a bottom nor one with both compiles:
+//!
+//! ```compile_fail
+//! # use lob::model::NewCheck;
+//! # let (to, from) = ("adr_d3489cd64c791ab5".parse::<lob::id::AddressId>().unwrap(), "adr_d3489cd64c791ab5".parse::<lob::id::AddressId>().unwrap());
+//! let check = NewCheck::builder(to, from, "bank_8cad8df5354d33f".parse().unwrap(), 12.50).build();
+//! ```
+//!
+//! ```compile_fail
+//! # use lob::model::{FileInput, NewCheck};
+//! # let (to, from) = ("adr_d3489cd64c791ab5".parse::<lob::id::AddressId>().unwrap(), "adr_d3489cd64c791ab5".parse::<lob::id::AddressId>().unwrap());
+//! let check = NewCheck::builder(to, from, "bank_8cad8df5354d33f".parse().unwrap(), 12.50)
+//!     .message("Thanks for your business!")
+//!     .check_bottom(FileInput::Html("<h1>Thanks!</h1>".to_owned()))
+//!     .build();
+//! ```
+//!
+//! Enum fields such as `size` take the enum itself, so a misspelled size does not compile either:
+//!
+//! ```compile_fail
+//! # use lob::model::{FileInput, NewPostcard};
+//! # let to = "adr_d3489cd64c791ab5".parse::<lob::id::AddressId>().unwrap();
+//! let postcard = NewPostcard::builder(to, FileInput::Html("front".to_owned()), FileInput::Html("back".to_owned()))
+//!     .size("6x8")
+//!     .build();
+//! ```
+
+use crate::id::BankAccountId;
+use crate::model::*;
+use chrono::{DateTime, NaiveDate, Utc};
+use std::collections::BTreeMap;
+use std::marker::PhantomData;
+
+/// Setters of the optional fields of the piece being built, each accepting anything that
+/// converts into the field's type.
+macro_rules! setters {
+    ($($field:ident: $ty:ty,)*) => {
+        $(
+            pub fn $field<T: Into<$ty>>(mut self, $field: T) -> Self {
+                self.inner.$field = Some($field.into());
+                self
+            }
+        )*
+    };
+}
+
+/// Setters of the optional enum fields, which take the enum itself, since a string would convert
+/// into its `Unknown` variant when misspelled instead of failing to compile.
+macro_rules! enum_setters {
+    ($($field:ident: $ty:ty,)*) => {
+        $(
+            pub fn $field(mut self, $field: $ty) -> Self {
+                self.inner.$field = Some($field);
+                self
+            }
+        )*
+    };
+}
+
+impl NewPostcard {
+    pub fn builder<T, F, B>(to: T, front: F, back: B) -> NewPostcardBuilder
+    where
+        T: Into<SendAddress>,
+        F: Into<FileInput>,
+        B: Into<FileInput>,
+    {
+        NewPostcardBuilder {
+            inner: NewPostcard {
+                description: None,
+                to: to.into(),
+                from: None,
+                front: front.into(),
+                back: back.into(),
+                merge_variables: None,
+                size: None,
+                mail_type: None,
+                use_type: None,
+                send_date: None,
+                metadata: None,
+            },
+        }
+    }
+}
+
+/// Builds a `NewPostcard`, see `NewPostcard::builder`.
+#[derive(Debug, Clone)]
+pub struct NewPostcardBuilder {
+    inner: NewPostcard,
+}
+
+impl NewPostcardBuilder {
+    setters! {
+        description: String,
+        from: SendAddress,
+        merge_variables: BTreeMap<String, String>,
+        send_date: DateTime<Utc>,
+        metadata: BTreeMap<String, String>,
+    }
+
+    enum_setters! {
+        size: PostcardSize,
+        mail_type: MailType,
+        use_type: UseType,
+    }
+
+    pub fn build(self) -> NewPostcard {
+        self.inner
+    }
+}
+
+impl NewLetter {
+    /// Letters are printed in black and white unless `color` is set.
+    pub fn builder<T, F, D>(to: T, from: F, file: D) -> NewLetterBuilder
+    where
+        T: Into<SendAddress>,
+        F: Into<SendAddress>,
+        D: Into<FileInput>,
+    {
+        NewLetterBuilder {
+            inner: NewLetter {
+                description: None,
+                to: to.into(),
+                from: from.into(),
+                color: false,
+                file: file.into(),
+                merge_variables: None,
+                double_sided: None,
+                address_placement: None,
+                return_envelope: None,
+                custom_envelope: None,
+                mail_type: None,
+                use_type: None,
+                extra_service: None,
+                send_date: None,
+                perforated_page: None,
+                metadata: None,
+            },
+        }
+    }
+}
+
+/// Builds a `NewLetter`, see `NewLetter::builder`.
+#[derive(Debug, Clone)]
+pub struct NewLetterBuilder {
+    inner: NewLetter,
+}
+
+impl NewLetterBuilder {
+    pub fn color(mut self, color: bool) -> Self {
+        self.inner.color = color;
+        self
+    }
+
+    setters! {
+        description: String,
+        merge_variables: BTreeMap<String, String>,
+        double_sided: bool,
+        return_envelope: bool,
+        custom_envelope: String,
+        send_date: DateTime<Utc>,
+        perforated_page: u32,
+        metadata: BTreeMap<String, String>,
+    }
+
+    enum_setters! {
+        address_placement: LetterAddressPlacement,
+        mail_type: MailType,
+        use_type: UseType,
+        extra_service: ExtraService,
+    }
+
+    pub fn build(self) -> NewLetter {
+        self.inner
+    }
+}
+
+impl NewCheck {
+    /// The bottom of the check is chosen next, with `message` or `check_bottom`.
+    pub fn builder<T, F, A>(
+        to: T,
+        from: F,
+        bank_account: BankAccountId,
+        amount: A,
+    ) -> NewCheckBuilder<NoBottom>
+    where
+        T: Into<SendAddress>,
+        F: Into<SendAddress>,
+        A: Into<CheckAmount>,
+    {
+        NewCheckBuilder {
+            inner: NewCheck {
+                description: None,
+                to: to.into(),
+                from: from.into(),
+                bank_account,
+                amount: amount.into(),
+                memo: None,
+                check_number: None,
+                logo: None,
+                message: None,
+                check_bottom: None,
+                attachment: None,
+                mail_type: None,
+                use_type: None,
+                send_date: None,
+                metadata: None,
+            },
+            bottom: PhantomData,
+        }
+    }
+}
+
+/// A check whose bottom is not chosen yet.
+#[derive(Debug, Clone, Copy)]
+pub struct NoBottom;
+
+/// A check with a `message` at its bottom.
+#[derive(Debug, Clone, Copy)]
+pub struct MessageBottom;
+
+/// A check with `check_bottom` artwork at its bottom.
+#[derive(Debug, Clone, Copy)]
+pub struct ArtworkBottom;
+
+/// Builds a `NewCheck`, see `NewCheck::builder`. `B` is one of `NoBottom`, `MessageBottom` and
+/// `ArtworkBottom`.
+#[derive(Debug, Clone)]
+pub struct NewCheckBuilder<B> {
+    inner: NewCheck,
+    bottom: PhantomData<B>,
+}
+
+impl<B> NewCheckBuilder<B> {
+    setters! {
+        description: String,
+        memo: String,
+        check_number: i32,
+        attachment: FileInput,
+        send_date: NaiveDate,
+        metadata: BTreeMap<String, String>,
+    }
+
+    enum_setters! {
+        mail_type: MailType,
+        use_type: UseType,
+    }
+
+    /// A logo at the top left of the check, from a remote URL.
+    pub fn logo_url<S: Into<String>>(mut self, url: S) -> Self {
+        self.inner.logo = Some(FileInput::Url(url.into()));
+        self
+    }
+
+    /// A logo at the top left of the check, uploaded with it.
+    pub fn logo_file<S: Into<String>>(mut self, filename: S, data: Vec<u8>) -> Self {
+        self.inner.logo = Some(FileInput::File {
+            filename: filename.into(),
+            data,
+        });
+        self
+    }
+
+    fn bottom<C>(self) -> NewCheckBuilder<C> {
+        NewCheckBuilder {
+            inner: self.inner,
+            bottom: PhantomData,
+        }
+    }
+}
+
+impl NewCheckBuilder<NoBottom> {
+    pub fn message<S: Into<String>>(mut self, message: S) -> NewCheckBuilder<MessageBottom> {
+        self.inner.message = Some(message.into());
+        self.bottom()
+    }
+
+    pub fn check_bottom<F: Into<FileInput>>(
+        mut self,
+        check_bottom: F,
+    ) -> NewCheckBuilder<ArtworkBottom> {
+        self.inner.check_bottom = Some(check_bottom.into());
+        self.bottom()
+    }
+}
+
+impl NewCheckBuilder<MessageBottom> {
+    pub fn build(self) -> NewCheck {
+        self.inner
+    }
+}
+
+impl NewCheckBuilder<ArtworkBottom> {
+    pub fn build(self) -> NewCheck {
+        self.inner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::id::{AddressId, TemplateId};
+    use serde_json::json;
+
+    fn to() -> SendAddress {
+        SendAddress::Components(SendAddressComponents {
+            name: "Jared Polis".to_owned(),
+            address_line1: "200 E Colfax Ave".to_owned(),
+            address_line2: None,
+            address_city: "Denver".to_owned(),
+            address_state: "CO".to_owned(),
+            address_zip: "80203".to_owned(),
+            address_country: None,
+        })
+    }
+
+    fn from() -> AddressId {
+        "adr_d3489cd64c791ab5".parse().unwrap()
+    }
+
+    #[test]
+    fn builds_postcards_and_letters() {
+        let template: TemplateId = "tmpl_c94e83ca2cd5121".parse().unwrap();
+        let postcard = NewPostcard::builder(
+            to(),
+            template.clone(),
+            FileInput::Url("https://example.com/back.pdf".to_owned()),
+        )
+        .from(from())
+        .size(PostcardSize::SixByNine)
+        .build();
+        assert_eq!(postcard.size, Some(PostcardSize::SixByNine));
+        assert!(postcard.from.is_some());
+
+        let letter = NewLetter::builder(to(), from(), template)
+            .color(true)
+            .mail_type(MailType::UspsFirstClass)
+            .perforated_page(1u32)
+            .build();
+        let letter = serde_json::to_value(&letter).unwrap();
+        assert_eq!(letter["color"], true);
+        assert_eq!(letter["file"], "tmpl_c94e83ca2cd5121");
+        assert_eq!(letter["from"], "adr_d3489cd64c791ab5");
+        assert_eq!(letter["mail_type"], "usps_first_class");
+        assert_eq!(letter["description"], json!(null));
+    }
+
+    #[test]
+    fn builds_checks_with_one_bottom() {
+        let bank_account: BankAccountId = "bank_8cad8df5354d33f".parse().unwrap();
+        let check = NewCheck::builder(to(), from(), bank_account.clone(), CheckAmount::new(12, 50))
+            .logo_url("https://example.com/logo.png")
+            .check_bottom(FileInput::Html("<h1>Thanks!</h1>".to_owned()))
+            .memo("rebate")
+            .build();
+        assert_eq!(check.message, None);
+        assert!(matches!(check.check_bottom, Some(FileInput::Html(_))));
+        assert_eq!(check.memo.as_deref(), Some("rebate"));
+        assert_eq!(check.amount, CheckAmount::new(12, 50));
+
+        let check = NewCheck::builder(to(), from(), bank_account, 12.50)
+            .message("Thanks!")
+            .build();
+        assert_eq!(check.message.as_deref(), Some("Thanks!"));
+        assert_eq!(check.check_bottom.map(|_| ()), None);
+    }
+}
@@ -0,0 +1,474 @@
+//! Record Lob's responses to a JSON file once, then replay them without network access.
+//!
+//! A `Cassette` passed to `ClientBuilder::cassette` either records, sending requests to Lob and
+//! keeping every request and response, or replays, answering requests from the file alone. A
+//! replayed request is matched by its method, its path and query relative to the base URL, and
+//! its JSON body or the fields of its multipart form, but not the uploaded files. Each recorded response is played at most once, in order, and a
+//! request without one fails with `ErrorKind::Cassette` instead of reaching the network.
+//!
+//! The API key is never written, and neither are bank account numbers: every `account_number`
+//! field is replaced with `REDACTED` in both requests and responses.
+
+use crate::client::RawResponse;
+use crate::error::Error;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// What replaces redacted values.
+pub const REDACTED: &str = "REDACTED";
+
+/// Fields whose values are redacted wherever they appear in a JSON body.
+const REDACTED_FIELDS: &[&str] = &["account_number"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Requests are sent to Lob, and kept along with their responses until `Cassette::save`.
+    Record,
+    /// Requests are answered from the cassette and never sent.
+    Replay,
+}
+
+#[derive(Clone)]
+pub struct Cassette {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    path: PathBuf,
+    mode: Mode,
+    state: Mutex<State>,
+}
+
+#[derive(Default)]
+struct State {
+    interactions: Vec<Interaction>,
+    played: Vec<bool>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct File {
+    interactions: Vec<Interaction>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct Interaction {
+    request: RecordedRequest,
+    response: RecordedResponse,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+struct RecordedRequest {
+    method: String,
+    path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body: Option<Value>,
+    /// The fields of a multipart body, without its files.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    form: Option<Value>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct RecordedResponse {
+    status: u16,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    headers: BTreeMap<String, String>,
+    /// The body, if it is JSON.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body: Option<Value>,
+    /// The body, if it is not JSON.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+}
+
+impl Cassette {
+    /// Record into `path`, replacing whatever it holds once saved.
+    pub fn record<P: Into<PathBuf>>(path: P) -> Cassette {
+        Cassette::new(path.into(), Mode::Record, Vec::new())
+    }
+
+    /// Replay the interactions recorded in `path`.
+    pub fn replay<P: Into<PathBuf>>(path: P) -> io::Result<Cassette> {
+        let path = path.into();
+        let file: File = serde_json::from_slice(&fs::read(&path)?)?;
+        Ok(Cassette::new(path, Mode::Replay, file.interactions))
+    }
+
+    /// Replay `path` if it exists, and record into it otherwise. Delete the file to record again.
+    pub fn open<P: Into<PathBuf>>(path: P) -> io::Result<Cassette> {
+        let path = path.into();
+        if path.exists() {
+            Cassette::replay(path)
+        } else {
+            Ok(Cassette::record(path))
+        }
+    }
+
+    fn new(path: PathBuf, mode: Mode, interactions: Vec<Interaction>) -> Cassette {
+        let played = vec![false; interactions.len()];
+        Cassette {
+            inner: Arc::new(Inner {
+                path,
+                mode,
+                state: Mutex::new(State {
+                    interactions,
+                    played,
+                }),
+            }),
+        }
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.inner.mode
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.inner.path
+    }
+
+    /// How many recorded responses have not been played yet. Always 0 while recording.
+    pub fn remaining(&self) -> usize {
+        self.state()
+            .played
+            .iter()
+            .filter(|played| !**played)
+            .count()
+    }
+
+    /// Write the recorded interactions to `path`, creating its directory if needed. Does
+    /// nothing when replaying.
+    pub fn save(&self) -> io::Result<()> {
+        if self.inner.mode == Mode::Replay {
+            return Ok(());
+        }
+        let file = File {
+            interactions: self.state().interactions.clone(),
+        };
+        if let Some(dir) = self.inner.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut json = serde_json::to_vec_pretty(&file)?;
+        json.push(b'\n');
+        fs::write(&self.inner.path, json)
+    }
+
+    pub(crate) async fn execute(
+        &self,
+        http: &reqwest::Client,
+        request: reqwest::Request,
+        form: Option<&Value>,
+        base_url: &str,
+        api_key: &str,
+    ) -> Result<RawResponse, Error> {
+        let recorded = RecordedRequest::new(&request, form, base_url, api_key);
+        match self.inner.mode {
+            Mode::Record => {
+                let response = RawResponse::read(http.execute(request).await?).await?;
+                self.state().record(Interaction {
+                    request: recorded,
+                    response: RecordedResponse::new(&response, api_key),
+                });
+                Ok(response)
+            }
+            Mode::Replay => self.state().play(&recorded).ok_or_else(|| {
+                Error::cassette(format!(
+                    "no recorded response left for {} {} in {}",
+                    recorded.method,
+                    recorded.path,
+                    self.inner.path.display()
+                ))
+            }),
+        }
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.inner
+            .state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl State {
+    fn record(&mut self, interaction: Interaction) {
+        self.interactions.push(interaction);
+        self.played.push(true);
+    }
+
+    fn play(&mut self, request: &RecordedRequest) -> Option<RawResponse> {
+        let index = self
+            .interactions
+            .iter()
+            .zip(&self.played)
+            .position(|(interaction, played)| !played && interaction.request == *request)?;
+        self.played[index] = true;
+        Some(self.interactions[index].response.to_raw())
+    }
+}
+
+impl RecordedRequest {
+    fn new(
+        request: &reqwest::Request,
+        form: Option<&Value>,
+        base_url: &str,
+        api_key: &str,
+    ) -> RecordedRequest {
+        let url = request.url().as_str();
+        let path = url.strip_prefix(base_url).unwrap_or(url);
+        let redacted = |mut value: Value| {
+            redact(&mut value, api_key);
+            value
+        };
+        // Multipart bodies are streamed, so they are recorded and matched by their fields instead
+        let body = request
+            .body()
+            .and_then(reqwest::Body::as_bytes)
+            .and_then(|body| serde_json::from_slice(body).ok())
+            .map(redacted);
+        RecordedRequest {
+            method: request.method().to_string(),
+            path: redact_str(path, api_key),
+            body,
+            form: form.cloned().map(redacted),
+        }
+    }
+}
+
+impl RecordedResponse {
+    fn new(response: &RawResponse, api_key: &str) -> RecordedResponse {
+        let mut headers = BTreeMap::new();
+        if let Some(content_type) = &response.content_type {
+            headers.insert("content-type".to_owned(), content_type.clone());
+        }
+        if let Some(retry_after) = &response.retry_after {
+            headers.insert("retry-after".to_owned(), retry_after.clone());
+        }
+        let (body, text) = match serde_json::from_slice::<Value>(&response.body) {
+            Ok(mut body) => {
+                redact(&mut body, api_key);
+                (Some(body), None)
+            }
+            Err(_) => {
+                let text = String::from_utf8_lossy(&response.body);
+                (None, Some(redact_str(&text, api_key)))
+            }
+        };
+        RecordedResponse {
+            status: response.status,
+            headers,
+            body,
+            text,
+        }
+    }
+
+    fn to_raw(&self) -> RawResponse {
+        let body = match (&self.body, &self.text) {
+            (Some(body), _) => body.to_string().into_bytes(),
+            (None, Some(text)) => text.clone().into_bytes(),
+            (None, None) => Vec::new(),
+        };
+        RawResponse {
+            status: self.status,
+            content_type: self.headers.get("content-type").cloned(),
+            retry_after: self.headers.get("retry-after").cloned(),
+            body,
+        }
+    }
+}
+
+fn redact(value: &mut Value, api_key: &str) {
+    match value {
+        Value::Object(fields) => {
+            for (name, value) in fields.iter_mut() {
+                if REDACTED_FIELDS.contains(&name.as_str()) && !value.is_null() {
+                    *value = Value::from(REDACTED);
+                } else {
+                    redact(value, api_key);
+                }
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(|value| redact(value, api_key)),
+        Value::String(s) => *s = redact_str(s, api_key),
+        Value::Null | Value::Bool(_) | Value::Number(_) => {}
+    }
+}
+
+fn redact_str(s: &str, api_key: &str) -> String {
+    if api_key.is_empty() {
+        s.to_owned()
+    } else {
+        s.replace(api_key, REDACTED)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorKind;
+    use crate::mock::{MockLob, MOCK_API_KEY};
+    use crate::model::*;
+    use crate::Client;
+    use rand::distributions::{Alphanumeric, DistString};
+    use tokio_test::block_on;
+
+    fn cassette_client(base_url: &str, cassette: &Cassette) -> Client {
+        Client::builder(MOCK_API_KEY)
+            .base_url(base_url)
+            .cassette(cassette.clone())
+            .build()
+            .unwrap()
+    }
+
+    fn bank_account() -> NewBankAccount {
+        NewBankAccount {
+            description: None,
+            routing_number: "021000021".to_string(),
+            account_number: "12345678901234".to_string(),
+            account_type: AccountType::Company,
+            signatory: "Jane Doe".to_string(),
+            metadata: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn records_redacted_interactions_and_replays_them() {
+        block_on(async {
+            let path = std::env::temp_dir()
+                .join(Alphanumeric.sample_string(&mut rand::thread_rng(), 16))
+                .join("bank_accounts.json");
+
+            let mock = MockLob::start().await;
+            let cassette = Cassette::open(&path).unwrap();
+            assert_eq!(cassette.mode(), Mode::Record);
+            let client = cassette_client(&mock.uri(), &cassette);
+            let created = client.create_bank_account(&bank_account()).await.unwrap();
+            let verified = client
+                .verify_bank_account(&created.id, [25, 63])
+                .await
+                .unwrap();
+            cassette.save().unwrap();
+
+            let recorded = fs::read_to_string(&path).unwrap();
+            assert!(!recorded.contains("12345678901234"));
+            assert!(!recorded.contains(MOCK_API_KEY));
+            assert!(recorded.contains(REDACTED));
+
+            // Nothing listens on the discard port, so any request that is not replayed fails
+            let cassette = Cassette::open(&path).unwrap();
+            assert_eq!(cassette.mode(), Mode::Replay);
+            assert_eq!(cassette.remaining(), 2);
+            let client = cassette_client("http://127.0.0.1:9/v1", &cassette);
+            let replayed = client.create_bank_account(&bank_account()).await.unwrap();
+            assert_eq!(replayed.id, created.id);
+            assert_eq!(replayed.account_number, REDACTED);
+            let replayed = client
+                .verify_bank_account(&created.id, [25, 63])
+                .await
+                .unwrap();
+            assert_eq!(replayed.verified, verified.verified);
+            assert_eq!(cassette.remaining(), 0);
+
+            let err = client.get_bank_account(&created.id).await.unwrap_err();
+            assert!(matches!(err.kind(), ErrorKind::Cassette(_)));
+            assert!(err.to_string().contains(&format!(
+                "no recorded response left for GET /bank_accounts/{}",
+                created.id
+            )));
+
+            fs::remove_dir_all(path.parent().unwrap()).unwrap();
+        })
+    }
+
+    fn letter(description: &str) -> NewLetter {
+        let address = SendAddressComponents {
+            name: "Jared Polis".to_owned(),
+            address_line1: "200 E Colfax Ave".to_owned(),
+            address_line2: None,
+            address_city: "Denver".to_owned(),
+            address_state: "CO".to_owned(),
+            address_zip: "80203".to_owned(),
+            address_country: None,
+        };
+        let file = FileInput::File {
+            filename: "letter.pdf".to_owned(),
+            data: b"%PDF-1.4".to_vec(),
+        };
+        NewLetter::builder(address.clone(), address, file)
+            .description(description)
+            .build()
+    }
+
+    #[test]
+    fn matches_multipart_requests_on_their_fields() {
+        block_on(async {
+            let path = std::env::temp_dir()
+                .join(Alphanumeric.sample_string(&mut rand::thread_rng(), 16))
+                .join("letters.json");
+
+            let mock = MockLob::start().await;
+            let cassette = Cassette::record(&path);
+            let client = cassette_client(&mock.uri(), &cassette);
+            let first = client.create_letter(letter("first")).await.unwrap();
+            let second = client.create_letter(letter("second")).await.unwrap();
+            cassette.save().unwrap();
+
+            let cassette = Cassette::replay(&path).unwrap();
+            let client = cassette_client("http://127.0.0.1:9/v1", &cassette);
+            let replayed = client.create_letter(letter("second")).await.unwrap();
+            assert_eq!(replayed.id, second.id);
+            let replayed = client.create_letter(letter("first")).await.unwrap();
+            assert_eq!(replayed.id, first.id);
+            assert_eq!(cassette.remaining(), 0);
+
+            fs::remove_dir_all(path.parent().unwrap()).unwrap();
+        })
+    }
+
+    /// The cassette is synthetic, recorded from `MockLob` rather than Lob, so it checks that
+    /// whole payloads survive a replay, but cannot catch drift from Lob's own schema.
+    #[test]
+    fn replays_mock_payloads() {
+        block_on(async {
+            let cassette = Cassette::replay(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/cassettes/mock_payloads.json"
+            ))
+            .unwrap();
+            let client = cassette_client("http://127.0.0.1:9/v1", &cassette);
+
+            let verification = client
+                .verify_us_address(
+                    AddressVerificationComponents {
+                        recipient: Some("Jared Polis".to_owned()),
+                        primary_line: "200 E Colfax Ave".to_string(),
+                        secondary_line: None,
+                        urbanization: None,
+                        city: Some("Denver".to_owned()),
+                        state: Some("CO".to_owned()),
+                        zip_code: Some("80203".to_owned()),
+                    },
+                    None,
+                )
+                .await
+                .unwrap();
+            assert_eq!(verification.deliverability, Deliverability::Deliverable);
+
+            let letter = client
+                .get_letter(&"ltr_0000000000000004".parse().unwrap())
+                .await
+                .unwrap();
+            assert_eq!(letter.description.as_deref(), Some("Welcome letter"));
+
+            let check = client
+                .get_check(&"chk_0000000000000007".parse().unwrap())
+                .await
+                .unwrap();
+            assert_eq!(check.bank_account.account_number, REDACTED);
+            assert_eq!(cassette.remaining(), 0);
+        })
+    }
+}
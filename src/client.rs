@@ -1,12 +1,37 @@
+use crate::api::LobApi;
+use crate::cassette::Cassette;
 use crate::error::Error;
+use crate::id::*;
 use crate::model::*;
-use reqwest::multipart::{Form, Part};
+use crate::multipart::{self, Files};
+use crate::retry::RetryPolicy;
+use crate::stream::ListStream;
+use rand::distributions::{Alphanumeric, DistString};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
-use std::mem;
-use serde_json::json;
+use std::time::Duration;
+use serde_json::{json, Value};
 
-pub static API_VERSION: &'static str = "2020-02-11";
+/// The Lob API version sent as `Lob-Version` on every request, unless overridden through
+/// `ClientBuilder::api_version`.
+///
+/// The types in `model` describe the response shapes of this version:
+///
+/// | Lob-Version  | Model shapes                                                             |
+/// |--------------|--------------------------------------------------------------------------|
+/// | `2020-02-11` | `Postcard`, `Letter`, `Check` and `BankAccount` as defined in this crate |
+///
+/// Responses of other versions may not deserialize, since fields are added, renamed and removed
+/// between versions. Only override the version to opt into one that is known to be compatible.
+pub static API_VERSION: &str = "2020-02-11";
+
+pub static DEFAULT_BASE_URL: &str = "https://api.lob.com/v1";
+
+/// The most addresses sent in one bulk verification request; larger inputs are split into chunks
+/// of this size.
+pub const MAX_BULK_VERIFICATIONS: usize = 20;
+
+const DEFAULT_USER_AGENT: &str = concat!("lob-rust/", env!("CARGO_PKG_VERSION"));
 
 const NO_QUERY: Option<&'static str> = None;
 
@@ -14,6 +39,13 @@ const NO_QUERY: Option<&'static str> = None;
 pub struct Client {
     inner: reqwest::Client,
     api_key: String,
+    base_url: String,
+    timeout: Option<Duration>,
+    user_agent: String,
+    api_version: String,
+    retry_policy: Option<RetryPolicy>,
+    cassette: Option<Cassette>,
+    validate_requests: bool,
 }
 
 impl Client {
@@ -21,24 +53,52 @@ impl Client {
         Client {
             inner: reqwest::Client::new(),
             api_key: api_key.into(),
+            base_url: DEFAULT_BASE_URL.to_owned(),
+            timeout: None,
+            user_agent: DEFAULT_USER_AGENT.to_owned(),
+            api_version: API_VERSION.to_owned(),
+            retry_policy: None,
+            cassette: None,
+            validate_requests: false,
         }
     }
 
+    pub fn builder<S: Into<String>>(api_key: S) -> ClientBuilder {
+        ClientBuilder::new(api_key)
+    }
+
+    /// The base URL every endpoint is resolved against, e.g. `https://api.lob.com/v1`.
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// The `Lob-Version` sent with every request, `API_VERSION` unless overridden.
+    pub fn api_version(&self) -> &str {
+        &self.api_version
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
     pub async fn create_address(&self, address: NewAddress) -> Result<Address, Error> {
-        self.post("https://api.lob.com/v1/addresses", &NO_QUERY, &address)
+        if self.validate_requests {
+            address.validate()?;
+        }
+        self.post(&self.url("/addresses"), &NO_QUERY, &address)
             .await
     }
 
-    pub async fn get_address(&self, id: &str) -> Result<Address, Error> {
+    pub async fn get_address(&self, id: &AddressId) -> Result<Address, Error> {
         self.get(
-            &format!("https://api.lob.com/v1/addresses/{}", id),
+            &self.url(&format!("/addresses/{}", id)),
             &NO_QUERY,
         )
             .await
     }
 
-    pub async fn delete_address(&self, id: &str) -> Result<Delete, Error> {
-        self.delete(&format!("https://api.lob.com/v1/addresses/{}", id))
+    pub async fn delete_address(&self, id: &AddressId) -> Result<Delete, Error> {
+        self.delete(&self.url(&format!("/addresses/{}", id)))
             .await
     }
 
@@ -46,10 +106,14 @@ impl Client {
         &self,
         options: Option<ListAddressesOptions>,
     ) -> Result<ListResponse<Address>, Error> {
-        self.get("https://api.lob.com/v1/addresses/", &options)
+        self.get(&self.url("/addresses/"), &options)
             .await
     }
 
+    pub fn list_addresses_stream(&self, options: Option<ListAddressesOptions>) -> ListStream<'_, Address> {
+        LobApi::list_addresses_stream(self, options)
+    }
+
     pub async fn verify_us_address<A: VerifyAddress>(
         &self,
         address: A,
@@ -58,7 +122,7 @@ impl Client {
         match address.into_input() {
             AddressVerificationInput::Flat(address) => {
                 self.post(
-                    "https://api.lob.com/v1/us_verifications",
+                    &self.url("/us_verifications"),
                     &options,
                     &("address", address),
                 )
@@ -66,7 +130,7 @@ impl Client {
             }
             AddressVerificationInput::Components(components) => {
                 self.post(
-                    "https://api.lob.com/v1/us_verifications",
+                    &self.url("/us_verifications"),
                     &options,
                     &components,
                 )
@@ -75,12 +139,32 @@ impl Client {
         }
     }
 
+    /// Verify many US addresses, `MAX_BULK_VERIFICATIONS` per request. The results are in the
+    /// order of `addresses`, each either the verification or the error Lob returned for that
+    /// address alone.
+    pub async fn bulk_verify_us_addresses<I>(
+        &self,
+        addresses: I,
+        options: Option<VerifyAddressOptions>,
+    ) -> Result<Vec<Result<UsVerification, LobError>>, Error>
+    where
+        I: IntoIterator,
+        I::Item: VerifyAddress,
+    {
+        let addresses: Vec<_> = addresses
+            .into_iter()
+            .map(VerifyAddress::into_input)
+            .collect();
+        self.bulk_verify("/bulk/us_verifications", &options, &addresses)
+            .await
+    }
+
     pub async fn autocomplete_address<S: Into<String>>(
         &self,
         address_prefix: S,
         options: Option<AutocompleteAddressOptions>,
     ) -> Result<UsAutocompletion, Error> {
-        let mut request = self.inner.post("https://api.lob.com/v1/us_autocompletions");
+        let mut request = self.inner.post(self.url("/us_autocompletions"));
         if let Some(true) = options.as_ref().and_then(|o| o.only_valid_addresses) {
             request = request.query(&[("valid_addresses", "true")]);
         }
@@ -97,7 +181,7 @@ impl Client {
 
     pub async fn us_zip_lookup<S: Into<String>>(&self, zip_code: S) -> Result<UsZipLookup, Error> {
         self.post(
-            "https://api.lob.com/v1/us_zip_lookups",
+            &self.url("/us_zip_lookups"),
             &NO_QUERY,
             &UsZipLookupBody {
                 zip_code: zip_code.into(),
@@ -111,40 +195,59 @@ impl Client {
         address: &InternationalVerificationInput,
     ) -> Result<InternationalVerification, Error> {
         self.post(
-            "https://api.lob.com/v1/intl_verifications",
+            &self.url("/intl_verifications"),
             &NO_QUERY,
             address,
         )
             .await
     }
 
-    pub async fn create_postcard(&self, mut postcard: NewPostcard) -> Result<Postcard, Error> {
-        let mut request = self.inner.post("https://api.lob.com/v1/postcards");
-        if let FileInput::File { filename, data } = &mut postcard.front {
-            let filename = mem::take(filename);
-            let data = mem::take(data);
-            request =
-                request.multipart(Form::new().part("front", Part::bytes(data).file_name(filename)));
-        }
-        if let FileInput::File { filename, data } = &mut postcard.back {
-            let filename = mem::take(filename);
-            let data = mem::take(data);
-            request =
-                request.multipart(Form::new().part("back", Part::bytes(data).file_name(filename)));
+    /// Verify many international addresses, `MAX_BULK_VERIFICATIONS` per request. The results
+    /// are in the order of `addresses`, each either the verification or the error Lob returned
+    /// for that address alone.
+    pub async fn bulk_verify_intl_addresses(
+        &self,
+        addresses: &[InternationalVerificationInput],
+    ) -> Result<Vec<Result<InternationalVerification, LobError>>, Error> {
+        self.bulk_verify("/bulk/intl_verifications", &NO_QUERY, addresses)
+            .await
+    }
+
+    pub async fn create_postcard(&self, postcard: NewPostcard) -> Result<Postcard, Error> {
+        self.create_postcard_with_options(postcard, RequestOptions::default())
+            .await
+    }
+
+    pub async fn create_postcard_with_options(
+        &self,
+        mut postcard: NewPostcard,
+        options: RequestOptions,
+    ) -> Result<Postcard, Error> {
+        if self.validate_requests {
+            postcard.validate()?;
         }
-        self.make_request(request.json(&postcard)).await
+        let options = generate_idempotency_key(options);
+        let url = self.url("/postcards");
+        let mut files = Files::default();
+        files.take("front", &mut postcard.front);
+        files.take("back", &mut postcard.back);
+        let form = multipart::form_fields(&postcard, &files)?;
+        self.send(form.as_ref(), || {
+            multipart::encode(self.post_with_options(&url, &options), &postcard, &files)
+        })
+        .await
     }
 
-    pub async fn get_postcard(&self, postcard_id: &str) -> Result<Postcard, Error> {
+    pub async fn get_postcard(&self, postcard_id: &PostcardId) -> Result<Postcard, Error> {
         self.get(
-            &format!("https://api.lob.com/v1/postcards/{}", postcard_id),
+            &self.url(&format!("/postcards/{}", postcard_id)),
             &NO_QUERY,
         )
             .await
     }
 
-    pub async fn cancel_postcard(&self, postcard_id: &str) -> Result<Delete, Error> {
-        self.delete(&format!("https://api.lob.com/v1/postcards/{}", postcard_id))
+    pub async fn cancel_postcard(&self, postcard_id: &PostcardId) -> Result<Delete, Error> {
+        self.delete(&self.url(&format!("/postcards/{}", postcard_id)))
             .await
     }
 
@@ -152,30 +255,47 @@ impl Client {
         &self,
         options: Option<ListPostcardOptions>,
     ) -> Result<ListResponse<Postcard>, Error> {
-        self.get("https://api.lob.com/v1/postcards", &options).await
+        self.get(&self.url("/postcards"), &options).await
+    }
+
+    pub fn list_postcards_stream(&self, options: Option<ListPostcardOptions>) -> ListStream<'_, Postcard> {
+        LobApi::list_postcards_stream(self, options)
+    }
+
+    pub async fn create_letter(&self, letter: NewLetter) -> Result<Letter, Error> {
+        self.create_letter_with_options(letter, RequestOptions::default())
+            .await
     }
 
-    pub async fn create_letter(&self, mut letter: NewLetter) -> Result<Letter, Error> {
-        let mut request = self.inner.post("https://api.lob.com/v1/letters");
-        if let FileInput::File { filename, data } = &mut letter.file {
-            let filename = mem::take(filename);
-            let data = mem::take(data);
-            request =
-                request.multipart(Form::new().part("file", Part::bytes(data).file_name(filename)));
+    pub async fn create_letter_with_options(
+        &self,
+        mut letter: NewLetter,
+        options: RequestOptions,
+    ) -> Result<Letter, Error> {
+        if self.validate_requests {
+            letter.validate()?;
         }
-        self.make_request(request.json(&letter)).await
+        let options = generate_idempotency_key(options);
+        let url = self.url("/letters");
+        let mut files = Files::default();
+        files.take("file", &mut letter.file);
+        let form = multipart::form_fields(&letter, &files)?;
+        self.send(form.as_ref(), || {
+            multipart::encode(self.post_with_options(&url, &options), &letter, &files)
+        })
+        .await
     }
 
-    pub async fn get_letter(&self, letter_id: &str) -> Result<Letter, Error> {
+    pub async fn get_letter(&self, letter_id: &LetterId) -> Result<Letter, Error> {
         self.get(
-            &format!("https://api.lob.com/v1/letters/{}", letter_id),
+            &self.url(&format!("/letters/{}", letter_id)),
             &NO_QUERY,
         )
             .await
     }
 
-    pub async fn cancel_letter(&self, letter_id: &str) -> Result<Delete, Error> {
-        self.delete(&format!("https://api.lob.com/v1/letters/{}", letter_id))
+    pub async fn cancel_letter(&self, letter_id: &LetterId) -> Result<Delete, Error> {
+        self.delete(&self.url(&format!("/letters/{}", letter_id)))
             .await
     }
 
@@ -183,15 +303,81 @@ impl Client {
         &self,
         options: Option<ListLetterOptions>,
     ) -> Result<ListResponse<Letter>, Error> {
-        self.get("https://api.lob.com/v1/letters", &options).await
+        self.get(&self.url("/letters"), &options).await
+    }
+
+    pub fn list_letters_stream(&self, options: Option<ListLetterOptions>) -> ListStream<'_, Letter> {
+        LobApi::list_letters_stream(self, options)
+    }
+
+    pub async fn create_self_mailer(&self, self_mailer: NewSelfMailer) -> Result<SelfMailer, Error> {
+        self.create_self_mailer_with_options(self_mailer, RequestOptions::default())
+            .await
+    }
+
+    pub async fn create_self_mailer_with_options(
+        &self,
+        mut self_mailer: NewSelfMailer,
+        options: RequestOptions,
+    ) -> Result<SelfMailer, Error> {
+        if self.validate_requests {
+            self_mailer.validate()?;
+        }
+        let options = generate_idempotency_key(options);
+        let url = self.url("/self_mailers");
+        let mut files = Files::default();
+        files.take("inside", &mut self_mailer.inside);
+        files.take("outside", &mut self_mailer.outside);
+        let form = multipart::form_fields(&self_mailer, &files)?;
+        self.send(form.as_ref(), || {
+            multipart::encode(self.post_with_options(&url, &options), &self_mailer, &files)
+        })
+        .await
+    }
+
+    pub async fn get_self_mailer(&self, self_mailer_id: &SelfMailerId) -> Result<SelfMailer, Error> {
+        self.get(
+            &self.url(&format!("/self_mailers/{}", self_mailer_id)),
+            &NO_QUERY,
+        )
+            .await
     }
 
-    pub async fn create_check(&self, mut check: NewCheck) -> Result<Check, Error> {
+    pub async fn cancel_self_mailer(&self, self_mailer_id: &SelfMailerId) -> Result<Delete, Error> {
+        self.delete(&self.url(&format!("/self_mailers/{}", self_mailer_id)))
+            .await
+    }
+
+    pub async fn list_self_mailers(
+        &self,
+        options: Option<ListSelfMailerOptions>,
+    ) -> Result<ListResponse<SelfMailer>, Error> {
+        self.get(&self.url("/self_mailers"), &options).await
+    }
+
+    pub fn list_self_mailers_stream(&self, options: Option<ListSelfMailerOptions>) -> ListStream<'_, SelfMailer> {
+        LobApi::list_self_mailers_stream(self, options)
+    }
+
+    pub async fn create_check(&self, check: NewCheck) -> Result<Check, Error> {
+        self.create_check_with_options(check, RequestOptions::default())
+            .await
+    }
+
+    pub async fn create_check_with_options(
+        &self,
+        mut check: NewCheck,
+        options: RequestOptions,
+    ) -> Result<Check, Error> {
+        if self.validate_requests {
+            check.validate()?;
+        }
         if let Some(logo) = &check.logo {
             if !(logo.is_file() || logo.is_url()) {
                 return Err(Error::bad_request("check bottom must be `File` or `URL`"));
             }
         }
+        // Checks from `NewCheck::builder` always pass, since their type tracks the bottom chosen
         match (&check.message, &check.check_bottom) {
             (Some(_), None) | (None, Some(_)) => {}
             _ => {
@@ -200,38 +386,29 @@ impl Client {
                 ));
             }
         }
-        let mut request = self.inner.post("https://api.lob.com/v1/checks");
-        if let Some(FileInput::File { filename, data }) = &mut check.logo {
-            let filename = mem::take(filename);
-            let data = mem::take(data);
-            request =
-                request.multipart(Form::new().part("logo", Part::bytes(data).file_name(filename)));
-        }
-        if let Some(FileInput::File { filename, data }) = &mut check.check_bottom {
-            let filename = mem::take(filename);
-            let data = mem::take(data);
-            request = request
-                .multipart(Form::new().part("check_bottom", Part::bytes(data).file_name(filename)));
-        }
-        if let Some(FileInput::File { filename, data }) = &mut check.attachment {
-            let filename = mem::take(filename);
-            let data = mem::take(data);
-            request = request
-                .multipart(Form::new().part("attachment", Part::bytes(data).file_name(filename)));
-        }
-        self.make_request(request.json(&check)).await
+        let options = generate_idempotency_key(options);
+        let url = self.url("/checks");
+        let mut files = Files::default();
+        files.take_maybe("logo", &mut check.logo);
+        files.take_maybe("check_bottom", &mut check.check_bottom);
+        files.take_maybe("attachment", &mut check.attachment);
+        let form = multipart::form_fields(&check, &files)?;
+        self.send(form.as_ref(), || {
+            multipart::encode(self.post_with_options(&url, &options), &check, &files)
+        })
+        .await
     }
 
-    pub async fn get_check(&self, check_id: &str) -> Result<Check, Error> {
+    pub async fn get_check(&self, check_id: &CheckId) -> Result<Check, Error> {
         self.get(
-            &format!("https://api.lob.com/v1/checks/{}", check_id),
+            &self.url(&format!("/checks/{}", check_id)),
             &NO_QUERY,
         )
             .await
     }
 
-    pub async fn cancel_check(&self, check_id: &str) -> Result<Delete, Error> {
-        self.delete(&format!("https://api.lob.com/v1/checks/{}", check_id))
+    pub async fn cancel_check(&self, check_id: &CheckId) -> Result<Delete, Error> {
+        self.delete(&self.url(&format!("/checks/{}", check_id)))
             .await
     }
 
@@ -239,7 +416,11 @@ impl Client {
         &self,
         options: Option<ListCheckOptions>,
     ) -> Result<ListResponse<Check>, Error> {
-        self.get("https://api.lob.com/v1/checks", &options).await
+        self.get(&self.url("/checks"), &options).await
+    }
+
+    pub fn list_checks_stream(&self, options: Option<ListCheckOptions>) -> ListStream<'_, Check> {
+        LobApi::list_checks_stream(self, options)
     }
 
     pub async fn create_bank_account(
@@ -247,39 +428,33 @@ impl Client {
         bank_account: &NewBankAccount,
     ) -> Result<BankAccount, Error> {
         self.post(
-            "https://api.lob.com/v1/bank_accounts",
+            &self.url("/bank_accounts"),
             &NO_QUERY,
             &bank_account,
         )
             .await
     }
 
-    pub async fn get_bank_account(&self, bank_account_id: &str) -> Result<BankAccount, Error> {
+    pub async fn get_bank_account(&self, bank_account_id: &BankAccountId) -> Result<BankAccount, Error> {
         self.get(
-            &format!("https://api.lob.com/v1/bank_accounts/{}", bank_account_id),
+            &self.url(&format!("/bank_accounts/{}", bank_account_id)),
             &NO_QUERY,
         )
             .await
     }
 
-    pub async fn delete_bank_account(&self, bank_account_id: &str) -> Result<Delete, Error> {
-        self.delete(&format!(
-            "https://api.lob.com/v1/bank_accounts/{}",
-            bank_account_id
-        ))
+    pub async fn delete_bank_account(&self, bank_account_id: &BankAccountId) -> Result<Delete, Error> {
+        self.delete(&self.url(&format!("/bank_accounts/{}", bank_account_id)))
             .await
     }
 
     pub async fn verify_bank_account(
         &self,
-        bank_account_id: &str,
+        bank_account_id: &BankAccountId,
         amounts: [u32; 2],
     ) -> Result<BankAccount, Error> {
         self.post(
-            &format!(
-                "https://api.lob.com/v1/bank_accounts/{}/verify",
-                bank_account_id
-            ),
+            &self.url(&format!("/bank_accounts/{}/verify", bank_account_id)),
             &NO_QUERY,
             &json!({ "amounts": amounts }),
         )
@@ -290,10 +465,305 @@ impl Client {
         &self,
         options: Option<ListBankAccountOptions>,
     ) -> Result<ListResponse<BankAccount>, Error> {
-        self.get("https://api.lob.com/v1/bank_accounts/", &options)
+        self.get(&self.url("/bank_accounts/"), &options)
+            .await
+    }
+
+    pub fn list_bank_accounts_stream(&self, options: Option<ListBankAccountOptions>) -> ListStream<'_, BankAccount> {
+        LobApi::list_bank_accounts_stream(self, options)
+    }
+
+    pub async fn create_template(&self, template: &NewTemplate) -> Result<Template, Error> {
+        self.post(&self.url("/templates"), &NO_QUERY, template)
+            .await
+    }
+
+    pub async fn get_template(&self, template_id: &TemplateId) -> Result<Template, Error> {
+        self.get(
+            &self.url(&format!("/templates/{}", template_id)),
+            &NO_QUERY,
+        )
+            .await
+    }
+
+    pub async fn update_template(
+        &self,
+        template_id: &TemplateId,
+        update: &UpdateTemplate,
+    ) -> Result<Template, Error> {
+        self.post(
+            &self.url(&format!("/templates/{}", template_id)),
+            &NO_QUERY,
+            update,
+        )
+            .await
+    }
+
+    pub async fn delete_template(&self, template_id: &TemplateId) -> Result<Delete, Error> {
+        self.delete(&self.url(&format!("/templates/{}", template_id)))
+            .await
+    }
+
+    pub async fn list_templates(
+        &self,
+        options: Option<ListTemplateOptions>,
+    ) -> Result<ListResponse<Template>, Error> {
+        self.get(&self.url("/templates/"), &options)
+            .await
+    }
+
+    pub fn list_templates_stream(&self, options: Option<ListTemplateOptions>) -> ListStream<'_, Template> {
+        LobApi::list_templates_stream(self, options)
+    }
+
+    /// Make `version_id` the version used when the template is referenced by its ID.
+    pub async fn publish_template_version(
+        &self,
+        template_id: &TemplateId,
+        version_id: &TemplateVersionId,
+    ) -> Result<Template, Error> {
+        let update = UpdateTemplate {
+            published_version: Some(version_id.clone()),
+            ..UpdateTemplate::default()
+        };
+        self.update_template(template_id, &update)
+            .await
+    }
+
+    pub async fn create_template_version(
+        &self,
+        template_id: &TemplateId,
+        version: &NewTemplateVersion,
+    ) -> Result<TemplateVersion, Error> {
+        self.post(
+            &self.url(&format!("/templates/{}/versions", template_id)),
+            &NO_QUERY,
+            version,
+        )
+            .await
+    }
+
+    pub async fn get_template_version(
+        &self,
+        template_id: &TemplateId,
+        version_id: &TemplateVersionId,
+    ) -> Result<TemplateVersion, Error> {
+        self.get(
+            &self.url(&format!("/templates/{}/versions/{}", template_id, version_id)),
+            &NO_QUERY,
+        )
+            .await
+    }
+
+    pub async fn update_template_version(
+        &self,
+        template_id: &TemplateId,
+        version_id: &TemplateVersionId,
+        update: &UpdateTemplateVersion,
+    ) -> Result<TemplateVersion, Error> {
+        self.post(
+            &self.url(&format!("/templates/{}/versions/{}", template_id, version_id)),
+            &NO_QUERY,
+            update,
+        )
+            .await
+    }
+
+    pub async fn delete_template_version(
+        &self,
+        template_id: &TemplateId,
+        version_id: &TemplateVersionId,
+    ) -> Result<Delete, Error> {
+        self.delete(&self.url(&format!("/templates/{}/versions/{}", template_id, version_id)))
+            .await
+    }
+
+    pub async fn list_template_versions(
+        &self,
+        template_id: &TemplateId,
+        options: Option<ListTemplateVersionOptions>,
+    ) -> Result<ListResponse<TemplateVersion>, Error> {
+        self.get(
+            &self.url(&format!("/templates/{}/versions", template_id)),
+            &options,
+        )
+            .await
+    }
+
+    pub fn list_template_versions_stream<'a>(
+        &'a self,
+        template_id: &'a TemplateId,
+        options: Option<ListTemplateVersionOptions>,
+    ) -> ListStream<'a, TemplateVersion> {
+        LobApi::list_template_versions_stream(self, template_id, options)
+    }
+
+    pub async fn create_campaign(&self, campaign: &NewCampaign) -> Result<Campaign, Error> {
+        self.post(&self.url("/campaigns"), &NO_QUERY, campaign)
+            .await
+    }
+
+    pub async fn get_campaign(&self, campaign_id: &CampaignId) -> Result<Campaign, Error> {
+        self.get(
+            &self.url(&format!("/campaigns/{}", campaign_id)),
+            &NO_QUERY,
+        )
+            .await
+    }
+
+    pub async fn update_campaign(
+        &self,
+        campaign_id: &CampaignId,
+        update: &UpdateCampaign,
+    ) -> Result<Campaign, Error> {
+        self.patch(&self.url(&format!("/campaigns/{}", campaign_id)), update)
+            .await
+    }
+
+    pub async fn delete_campaign(&self, campaign_id: &CampaignId) -> Result<Delete, Error> {
+        self.delete(&self.url(&format!("/campaigns/{}", campaign_id)))
+            .await
+    }
+
+    pub async fn list_campaigns(
+        &self,
+        options: Option<ListCampaignOptions>,
+    ) -> Result<ListResponse<Campaign>, Error> {
+        self.get(&self.url("/campaigns"), &options)
+            .await
+    }
+
+    pub fn list_campaigns_stream(&self, options: Option<ListCampaignOptions>) -> ListStream<'_, Campaign> {
+        LobApi::list_campaigns_stream(self, options)
+    }
+
+    /// Send the campaign's creative to every validated row of its upload.
+    pub async fn send_campaign(&self, campaign_id: &CampaignId) -> Result<Campaign, Error> {
+        self.post(
+            &self.url(&format!("/campaigns/{}/send", campaign_id)),
+            &NO_QUERY,
+            &json!({}),
+        )
+            .await
+    }
+
+    pub async fn create_creative(&self, creative: &NewCreative) -> Result<Creative, Error> {
+        let url = self.url("/creatives");
+        let mut creative = creative.clone();
+        let mut files = Files::default();
+        files.take_maybe("front", &mut creative.front);
+        files.take_maybe("back", &mut creative.back);
+        files.take_maybe("file", &mut creative.file);
+        let form = multipart::form_fields(&creative, &files)?;
+        self.send(form.as_ref(), || {
+            multipart::encode(self.inner.post(&url), &creative, &files)
+        })
+        .await
+    }
+
+    pub async fn get_creative(&self, creative_id: &CreativeId) -> Result<Creative, Error> {
+        self.get(
+            &self.url(&format!("/creatives/{}", creative_id)),
+            &NO_QUERY,
+        )
+            .await
+    }
+
+    pub async fn update_creative(
+        &self,
+        creative_id: &CreativeId,
+        update: &UpdateCreative,
+    ) -> Result<Creative, Error> {
+        self.patch(&self.url(&format!("/creatives/{}", creative_id)), update)
+            .await
+    }
+
+    pub async fn create_upload(&self, upload: &NewUpload) -> Result<Upload, Error> {
+        self.post(&self.url("/uploads"), &NO_QUERY, upload)
+            .await
+    }
+
+    /// Poll this to follow the upload's `state` while Lob validates its file.
+    pub async fn get_upload(&self, upload_id: &UploadId) -> Result<Upload, Error> {
+        self.get(
+            &self.url(&format!("/uploads/{}", upload_id)),
+            &NO_QUERY,
+        )
+            .await
+    }
+
+    pub async fn list_uploads(&self, campaign_id: Option<&CampaignId>) -> Result<Vec<Upload>, Error> {
+        let query = campaign_id.map(|campaign_id| ListUploadsQuery { campaign_id });
+        self.get(&self.url("/uploads"), &query)
+            .await
+    }
+
+    /// Upload the audience CSV of `upload_id`, with one mail piece per row.
+    pub async fn upload_file<S: Into<String>>(
+        &self,
+        upload_id: &UploadId,
+        filename: S,
+        data: Vec<u8>,
+    ) -> Result<UploadedFile, Error> {
+        let url = self.url(&format!("/uploads/{}/file", upload_id));
+        let mut files = Files::default();
+        files.take(
+            "file",
+            &mut FileInput::File {
+                filename: filename.into(),
+                data,
+            },
+        );
+        let form = multipart::form_fields(&json!({}), &files)?;
+        self.send(form.as_ref(), || {
+            multipart::encode(self.inner.post(&url), &json!({}), &files)
+        })
+        .await
+    }
+
+    /// Start generating a report of the upload's rows, e.g. `ExportType::Failures` for the rows
+    /// that failed validation. Poll `get_upload_export` until it is ready.
+    pub async fn create_upload_export(
+        &self,
+        upload_id: &UploadId,
+        export_type: ExportType,
+    ) -> Result<NewUploadExport, Error> {
+        self.post(
+            &self.url(&format!("/uploads/{}/exports", upload_id)),
+            &NO_QUERY,
+            &UploadExportBody { export_type },
+        )
+            .await
+    }
+
+    pub async fn get_upload_export(
+        &self,
+        upload_id: &UploadId,
+        export_id: &UploadExportId,
+    ) -> Result<UploadExport, Error> {
+        self.get(
+            &self.url(&format!("/uploads/{}/exports/{}", upload_id, export_id)),
+            &NO_QUERY,
+        )
             .await
     }
 
+    pub async fn get_event(&self, event_id: &EventId) -> Result<Event, Error> {
+        self.get(&self.url(&format!("/events/{}", event_id)), &NO_QUERY)
+            .await
+    }
+
+    pub async fn list_events(
+        &self,
+        options: Option<ListEventOptions>,
+    ) -> Result<ListResponse<Event>, Error> {
+        self.get(&self.url("/events"), &options).await
+    }
+
+    pub fn list_events_stream(&self, options: Option<ListEventOptions>) -> ListStream<'_, Event> {
+        LobApi::list_events_stream(self, options)
+    }
+
     async fn post<Q: Serialize, B: Serialize, R: DeserializeOwned + 'static>(
         &self,
         url: &str,
@@ -301,20 +771,68 @@ impl Client {
         body: &B,
     ) -> Result<R, Error> {
         let query = make_query_string(query)?;
-        self.make_request(self.inner.post(&format!("{}{}", url, query)).json(body))
+        self.make_request(self.inner.post(format!("{}{}", url, query)).json(body))
             .await
     }
 
+    fn post_with_options(&self, url: &str, options: &RequestOptions) -> reqwest::RequestBuilder {
+        let request = self.inner.post(url);
+        match &options.idempotency_key {
+            Some(key) => request.header("Idempotency-Key", key),
+            None => request,
+        }
+    }
+
+    async fn bulk_verify<Q: Serialize, A: Serialize, R: DeserializeOwned + 'static>(
+        &self,
+        path: &str,
+        query: &Option<Q>,
+        addresses: &[A],
+    ) -> Result<Vec<Result<R, LobError>>, Error> {
+        let mut results = Vec::with_capacity(addresses.len());
+        for chunk in addresses.chunks(MAX_BULK_VERIFICATIONS) {
+            let response: BulkVerificationResponse<R> = self
+                .post(&self.url(path), query, &BulkVerificationBody { addresses: chunk })
+                .await?;
+            // Results are matched to addresses by position, which a short page would shift
+            if response.addresses.len() != chunk.len() {
+                return Err(Error::decode(
+                    "addresses",
+                    format_args!(
+                        "expected {} results, got {}",
+                        chunk.len(),
+                        response.addresses.len()
+                    ),
+                ));
+            }
+            results.extend(
+                response
+                    .addresses
+                    .into_iter()
+                    .map(BulkVerificationEntry::into_result),
+            );
+        }
+        Ok(results)
+    }
+
     async fn get<Q: Serialize, R: DeserializeOwned + 'static>(
         &self,
         url: &str,
         query: &Option<Q>,
     ) -> Result<R, Error> {
         let query = make_query_string(query)?;
-        self.make_request(self.inner.get(&format!("{}{}", url, query)))
+        self.make_request(self.inner.get(format!("{}{}", url, query)))
             .await
     }
 
+    async fn patch<B: Serialize, R: DeserializeOwned + 'static>(
+        &self,
+        url: &str,
+        body: &B,
+    ) -> Result<R, Error> {
+        self.make_request(self.inner.patch(url).json(body)).await
+    }
+
     async fn delete<R: DeserializeOwned + 'static>(&self, url: &str) -> Result<R, Error> {
         self.make_request(self.inner.delete(url)).await
     }
@@ -323,19 +841,262 @@ impl Client {
         &self,
         request: reqwest::RequestBuilder,
     ) -> Result<R, Error> {
-        let response = request
+        // Only `send` is given streaming bodies, so these can always be cloned
+        self.send(None, || {
+            request
+                .try_clone()
+                .ok_or_else(|| Error::bad_request("request body cannot be cloned"))
+        })
+        .await
+    }
+
+    /// Sends the request `build` returns, building it again for each retry, since a multipart
+    /// body is streamed and cannot be cloned once built. `form` holds the fields of a multipart
+    /// body, from `multipart::form_fields`.
+    async fn send<R, F>(&self, form: Option<&Value>, build: F) -> Result<R, Error>
+    where
+        R: DeserializeOwned,
+        F: Fn() -> Result<reqwest::RequestBuilder, Error>,
+    {
+        let request = self.prepare(build()?)?;
+        let policy = match &self.retry_policy {
+            Some(policy) if is_safe_to_retry(&request) => policy,
+            _ => return self.execute(request, form).await,
+        };
+        let mut request = request;
+        let mut attempt = 1;
+        loop {
+            match self.execute(request, form).await {
+                Err(err) if policy.retries(&err, attempt) => {
+                    tokio::time::sleep(policy.backoff(&err, attempt)).await;
+                    request = self.prepare(build()?)?;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    fn prepare(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Request, Error> {
+        let mut request = request
             .basic_auth(&self.api_key, Option::<String>::None)
-            .send()
-            .await?;
+            .header(reqwest::header::USER_AGENT, &self.user_agent)
+            .header("Lob-Version", &self.api_version);
+        if let Some(timeout) = self.timeout {
+            request = request.timeout(timeout);
+        }
+        Ok(request.build()?)
+    }
+
+    async fn execute<R: DeserializeOwned>(
+        &self,
+        request: reqwest::Request,
+        form: Option<&Value>,
+    ) -> Result<R, Error> {
+        let response = match &self.cassette {
+            Some(cassette) => {
+                cassette
+                    .execute(&self.inner, request, form, &self.base_url, &self.api_key)
+                    .await?
+            }
+            None => RawResponse::read(self.inner.execute(request).await?).await?,
+        };
 
-        if response.status().is_success() {
-            let response = response.json().await?;
-            Ok(response)
+        if (200..300).contains(&response.status) {
+            let mut deserializer = serde_json::Deserializer::from_slice(&response.body);
+            let body = serde_path_to_error::deserialize(&mut deserializer)?;
+            deserializer.end()?;
+            Ok(body)
         } else {
-            let LobErrorResponse { error } = response.json().await?;
-            Err(error.into())
+            let retry_after = retry_after(response.status, response.retry_after.as_deref());
+            let body = String::from_utf8_lossy(&response.body).into_owned();
+            let err = match serde_json::from_str::<LobErrorResponse>(&body) {
+                Ok(LobErrorResponse { error }) => Error::from(error),
+                Err(_) => Error::unexpected_response(response.status, response.content_type, &body),
+            };
+            Err(err
+                .with_response(response.status, body)
+                .with_retry_after(retry_after))
+        }
+    }
+}
+
+/// The parts of a response that `Client` looks at, read in full so that it can be recorded.
+pub(crate) struct RawResponse {
+    pub status: u16,
+    pub content_type: Option<String>,
+    pub retry_after: Option<String>,
+    pub body: Vec<u8>,
+}
+
+impl RawResponse {
+    pub(crate) async fn read(response: reqwest::Response) -> Result<RawResponse, Error> {
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v: &reqwest::header::HeaderValue| v.to_str().ok())
+                .map(str::to_owned)
+        };
+        let status = response.status().as_u16();
+        let content_type = header(reqwest::header::CONTENT_TYPE);
+        let retry_after = header(reqwest::header::RETRY_AFTER);
+        let body = response.bytes().await?.to_vec();
+        Ok(RawResponse {
+            status,
+            content_type,
+            retry_after,
+            body,
+        })
+    }
+}
+
+/// Fills in the key `RequestOptions::generate_idempotency_key` asks for, once per call, so that
+/// every retry of the call sends the same one.
+fn generate_idempotency_key(mut options: RequestOptions) -> RequestOptions {
+    if options.generate_idempotency_key && options.idempotency_key.is_none() {
+        options.idempotency_key = Some(Alphanumeric.sample_string(&mut rand::thread_rng(), 32));
+    }
+    options
+}
+
+/// Everything but `POST` is idempotent, and a `POST` is made so by an `Idempotency-Key`.
+fn is_safe_to_retry(request: &reqwest::Request) -> bool {
+    request.method() != reqwest::Method::POST || request.headers().contains_key("Idempotency-Key")
+}
+
+/// `Retry-After` is either a number of seconds or an HTTP date, and only honored on 429 and 503.
+fn retry_after(status: u16, value: Option<&str>) -> Option<Duration> {
+    match status {
+        429 | 503 => {}
+        _ => return None,
+    }
+    let value = value?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    (date.with_timezone(&chrono::Utc) - chrono::Utc::now())
+        .to_std()
+        .ok()
+}
+
+pub struct ClientBuilder {
+    api_key: String,
+    base_url: String,
+    connect_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    user_agent: String,
+    api_version: String,
+    http_client: Option<reqwest::Client>,
+    retry_policy: Option<RetryPolicy>,
+    cassette: Option<Cassette>,
+    validate_requests: bool,
+}
+
+impl ClientBuilder {
+    pub fn new<S: Into<String>>(api_key: S) -> ClientBuilder {
+        ClientBuilder {
+            api_key: api_key.into(),
+            base_url: DEFAULT_BASE_URL.to_owned(),
+            connect_timeout: None,
+            timeout: None,
+            user_agent: DEFAULT_USER_AGENT.to_owned(),
+            api_version: API_VERSION.to_owned(),
+            http_client: None,
+            retry_policy: None,
+            cassette: None,
+            validate_requests: false,
         }
     }
+
+    /// Every endpoint is resolved against this URL, e.g. `http://localhost:8080/v1` for a local
+    /// stand-in or a proxy. Defaults to `https://api.lob.com/v1`.
+    pub fn base_url<S: Into<String>>(mut self, base_url: S) -> ClientBuilder {
+        self.base_url = base_url.into().trim_end_matches('/').to_owned();
+        self
+    }
+
+    /// Timeout for establishing a connection. Cannot be combined with `http_client`, since the
+    /// connector of a pre-built client is already configured.
+    pub fn connect_timeout(mut self, timeout: Duration) -> ClientBuilder {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Timeout for a whole request, from sending it until the response body has been read.
+    pub fn timeout(mut self, timeout: Duration) -> ClientBuilder {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn user_agent<S: Into<String>>(mut self, user_agent: S) -> ClientBuilder {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    /// Pin requests to a Lob API version other than `API_VERSION`. See `API_VERSION` for which
+    /// versions the models in this crate match.
+    pub fn api_version<S: Into<String>>(mut self, api_version: S) -> ClientBuilder {
+        self.api_version = api_version.into();
+        self
+    }
+
+    /// Use a pre-built `reqwest::Client`, e.g. to share a connection pool or configure a proxy.
+    pub fn http_client(mut self, http_client: reqwest::Client) -> ClientBuilder {
+        self.http_client = Some(http_client);
+        self
+    }
+
+    /// Retry failed requests according to `policy`. Requests are not retried by default.
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> ClientBuilder {
+        self.retry_policy = Some(policy);
+        self
+    }
+
+    /// Record responses into, or replay them from, `cassette` instead of only talking to Lob. See
+    /// `Cassette` for how requests are matched and what is redacted.
+    pub fn cassette(mut self, cassette: Cassette) -> ClientBuilder {
+        self.cassette = Some(cassette);
+        self
+    }
+
+    /// Check new addresses, postcards, letters, self-mailers and checks with their `validate`
+    /// before sending them, failing with `ErrorKind::Validation` instead of a request Lob would
+    /// reject. Off by default.
+    pub fn validate_requests(mut self, validate_requests: bool) -> ClientBuilder {
+        self.validate_requests = validate_requests;
+        self
+    }
+
+    pub fn build(self) -> Result<Client, Error> {
+        let inner = match (self.http_client, self.connect_timeout) {
+            (Some(_), Some(_)) => {
+                return Err(Error::bad_request(
+                    "`connect_timeout` cannot be applied to a provided `http_client`",
+                ));
+            }
+            (Some(http_client), None) => http_client,
+            (None, connect_timeout) => {
+                let mut builder = reqwest::Client::builder();
+                if let Some(connect_timeout) = connect_timeout {
+                    builder = builder.connect_timeout(connect_timeout);
+                }
+                builder.build()?
+            }
+        };
+        Ok(Client {
+            inner,
+            api_key: self.api_key,
+            base_url: self.base_url,
+            timeout: self.timeout,
+            user_agent: self.user_agent,
+            api_version: self.api_version,
+            retry_policy: self.retry_policy,
+            cassette: self.cassette,
+            validate_requests: self.validate_requests,
+        })
+    }
 }
 
 fn make_query_string<S: Serialize>(options: &Option<S>) -> Result<String, Error> {
@@ -346,3 +1107,928 @@ fn make_query_string<S: Serialize>(options: &Option<S>) -> Result<String, Error>
         Ok(String::new())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorKind;
+    use std::collections::BTreeMap;
+    use tokio_test::block_on;
+    use wiremock::matchers::{body_json, header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    fn routes_through_base_url() {
+        block_on(async {
+            let server = MockServer::start().await;
+            Mock::given(method("DELETE"))
+                .and(path("/v1/addresses/adr_123"))
+                .and(header("user-agent", "lob-test"))
+                .and(header("Lob-Version", API_VERSION))
+                .respond_with(
+                    ResponseTemplate::new(200)
+                        .set_body_json(json!({ "id": "adr_123", "deleted": true })),
+                )
+                .expect(1)
+                .mount(&server)
+                .await;
+
+            let client = Client::builder("test_key")
+                .base_url(format!("{}/v1/", server.uri()))
+                .user_agent("lob-test")
+                .timeout(Duration::from_secs(5))
+                .build()
+                .unwrap();
+            assert_eq!(client.base_url(), format!("{}/v1", server.uri()));
+            let delete = client.delete_address(&"adr_123".parse().unwrap()).await.unwrap();
+            assert_eq!(delete.id, "adr_123");
+            assert!(delete.deleted);
+        })
+    }
+
+    fn unavailable() -> ResponseTemplate {
+        ResponseTemplate::new(503)
+            .insert_header("Retry-After", "0")
+            .set_body_json(json!({ "error": { "message": "try again", "status_code": 503 } }))
+    }
+
+    fn retrying_client(server: &MockServer, policy: RetryPolicy) -> Client {
+        Client::builder("test_key")
+            .base_url(server.uri())
+            .retry_policy(policy.initial_backoff(Duration::from_millis(1)))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn retries_idempotent_requests() {
+        block_on(async {
+            let server = MockServer::start().await;
+            Mock::given(method("DELETE"))
+                .respond_with(unavailable())
+                .up_to_n_times(2)
+                .expect(2)
+                .mount(&server)
+                .await;
+            Mock::given(method("DELETE"))
+                .respond_with(
+                    ResponseTemplate::new(200)
+                        .set_body_json(json!({ "id": "adr_123", "deleted": true })),
+                )
+                .expect(1)
+                .mount(&server)
+                .await;
+
+            let client = retrying_client(&server, RetryPolicy::new(3));
+            let delete = client.delete_address(&"adr_123".parse().unwrap()).await.unwrap();
+            assert!(delete.deleted);
+        })
+    }
+
+    #[test]
+    fn does_not_retry_post_without_idempotency_key() {
+        block_on(async {
+            let server = MockServer::start().await;
+            Mock::given(method("POST"))
+                .respond_with(unavailable())
+                .expect(1)
+                .mount(&server)
+                .await;
+
+            let client = retrying_client(&server, RetryPolicy::new(3));
+            let err = client.us_zip_lookup("80303").await.unwrap_err();
+            assert!(err.is_retryable());
+            assert_eq!(err.retry_after(), Some(Duration::ZERO));
+        })
+    }
+
+    #[test]
+    fn retry_hook_vetoes_retries() {
+        block_on(async {
+            let server = MockServer::start().await;
+            Mock::given(method("GET"))
+                .respond_with(unavailable())
+                .expect(1)
+                .mount(&server)
+                .await;
+
+            let client = retrying_client(&server, RetryPolicy::new(3).should_retry(|_, _| false));
+            assert!(client.get_letter(&"ltr_123".parse().unwrap()).await.is_err());
+        })
+    }
+
+    fn letter() -> NewLetter {
+        NewLetter {
+            description: None,
+            to: SendAddress::AddressId("adr_to".parse().unwrap()),
+            from: SendAddress::AddressId("adr_from".parse().unwrap()),
+            color: false,
+            file: FileInput::File {
+                filename: "letter.pdf".into(),
+                data: b"%PDF".to_vec(),
+            },
+            merge_variables: None,
+            double_sided: None,
+            address_placement: None,
+            return_envelope: None,
+            custom_envelope: None,
+            mail_type: None,
+            use_type: None,
+            extra_service: None,
+            send_date: None,
+            perforated_page: None,
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn generates_one_idempotency_key_per_call() {
+        block_on(async {
+            let server = MockServer::start().await;
+            Mock::given(method("POST"))
+                .and(path("/letters"))
+                .respond_with(unavailable())
+                .expect(4)
+                .mount(&server)
+                .await;
+
+            // The same letter, sent twice on purpose, must not be deduplicated into one
+            let client = retrying_client(&server, RetryPolicy::new(2));
+            for _ in 0..2 {
+                let options = RequestOptions::with_generated_idempotency_key();
+                let err = client
+                    .create_letter_with_options(letter(), options)
+                    .await
+                    .unwrap_err();
+                assert!(err.is_retryable());
+            }
+
+            let keys: Vec<_> = server
+                .received_requests()
+                .await
+                .unwrap()
+                .iter()
+                .map(|request| request.headers["idempotency-key"].clone())
+                .collect();
+            assert_eq!(keys[0], keys[1]);
+            assert_eq!(keys[2], keys[3]);
+            assert_ne!(keys[0], keys[2]);
+        })
+    }
+
+    #[test]
+    fn retries_post_with_idempotency_key() {
+        block_on(async {
+            let server = MockServer::start().await;
+            Mock::given(method("POST"))
+                .and(path("/checks"))
+                .and(header("Idempotency-Key", "check-1"))
+                .respond_with(unavailable())
+                .expect(3)
+                .mount(&server)
+                .await;
+
+            let client = retrying_client(&server, RetryPolicy::new(3));
+            let check = NewCheck {
+                description: None,
+                to: SendAddress::AddressId("adr_to".parse().unwrap()),
+                from: SendAddress::AddressId("adr_from".parse().unwrap()),
+                bank_account: "bank_123".parse().unwrap(),
+                amount: CheckAmount::new(10, 0),
+                memo: None,
+                check_number: None,
+                logo: None,
+                message: Some("a message!".into()),
+                check_bottom: None,
+                attachment: None,
+                mail_type: None,
+                use_type: None,
+                send_date: None,
+                metadata: None,
+            };
+            let err = client
+                .create_check_with_options(check, RequestOptions::with_idempotency_key("check-1"))
+                .await
+                .unwrap_err();
+            assert!(err.is_retryable());
+        })
+    }
+
+    #[test]
+    fn retries_multipart_uploads_with_idempotency_key() {
+        block_on(async {
+            let server = MockServer::start().await;
+            Mock::given(method("POST"))
+                .and(path("/postcards"))
+                .respond_with(unavailable())
+                .up_to_n_times(1)
+                .expect(1)
+                .mount(&server)
+                .await;
+            Mock::given(method("POST"))
+                .and(path("/postcards"))
+                .respond_with(
+                    ResponseTemplate::new(200).set_body_json(crate::fixtures::postcard("psc_123")),
+                )
+                .expect(1)
+                .mount(&server)
+                .await;
+
+            let client = retrying_client(&server, RetryPolicy::new(3));
+            let postcard = NewPostcard {
+                description: None,
+                to: SendAddress::AddressId("adr_to".parse().unwrap()),
+                from: None,
+                front: FileInput::File {
+                    filename: "front.png".into(),
+                    data: b"front-bytes".to_vec(),
+                },
+                back: FileInput::Html("<h1>Back</h1>".into()),
+                merge_variables: None,
+                size: None,
+                mail_type: None,
+                use_type: None,
+                send_date: None,
+                metadata: None,
+            };
+            let postcard = client
+                .create_postcard_with_options(
+                    postcard,
+                    RequestOptions::with_idempotency_key("postcard-1"),
+                )
+                .await
+                .unwrap();
+            assert_eq!(postcard.id.as_str(), "psc_123");
+
+            // Both attempts upload the whole form
+            let requests = server.received_requests().await.unwrap();
+            assert_eq!(requests.len(), 2);
+            for request in &requests {
+                assert_eq!(request.headers["idempotency-key"], "postcard-1");
+                assert!(String::from_utf8_lossy(&request.body).contains("front-bytes"));
+            }
+        })
+    }
+
+    #[test]
+    fn uploads_files_and_fields_in_one_form() {
+        block_on(async {
+            let server = MockServer::start().await;
+            Mock::given(method("POST"))
+                .and(path("/postcards"))
+                .respond_with(ResponseTemplate::new(422).set_body_json(
+                    json!({ "error": { "message": "invalid", "status_code": 422 } }),
+                ))
+                .expect(1)
+                .mount(&server)
+                .await;
+
+            let client = Client::builder("test_key")
+                .base_url(server.uri())
+                .build()
+                .unwrap();
+            let mut metadata = BTreeMap::new();
+            metadata.insert("customer_id".to_owned(), "987654".to_owned());
+            let postcard = NewPostcard {
+                description: Some("with files".into()),
+                to: SendAddress::Components(SendAddressComponents {
+                    name: "Jared Polis".to_string(),
+                    address_line1: "200 E Colfax Ave".to_string(),
+                    address_line2: None,
+                    address_city: "Denver".to_string(),
+                    address_state: "CO".to_string(),
+                    address_zip: "80203".to_string(),
+                    address_country: None,
+                }),
+                from: Some(SendAddress::AddressId("adr_from".parse().unwrap())),
+                front: FileInput::File {
+                    filename: "front.png".into(),
+                    data: b"front-bytes".to_vec(),
+                },
+                back: FileInput::File {
+                    filename: "back.png".into(),
+                    data: b"back-bytes".to_vec(),
+                },
+                merge_variables: None,
+                size: Some(PostcardSize::FourBySix),
+                mail_type: None,
+                use_type: None,
+                send_date: None,
+                metadata: Some(metadata),
+            };
+            assert!(client.create_postcard(postcard).await.is_err());
+
+            let requests = server.received_requests().await.unwrap();
+            let content_type = requests[0].headers["content-type"].to_str().unwrap();
+            assert!(content_type.starts_with("multipart/form-data"));
+            let body = String::from_utf8_lossy(&requests[0].body);
+            for part in [
+                r#"name="front"; filename="front.png""#,
+                "front-bytes",
+                r#"name="back"; filename="back.png""#,
+                "back-bytes",
+                r#"name="to[address_line1]""#,
+                r#"name="from""#,
+                r#"name="metadata[customer_id]""#,
+                "987654",
+                r#"name="size""#,
+            ] {
+                assert!(body.contains(part), "missing {} in {}", part, body);
+            }
+            assert!(!body.contains(r#"name="to[address_line2]""#));
+        })
+    }
+
+    #[test]
+    fn uploads_creative_files_in_a_form() {
+        block_on(async {
+            let server = MockServer::start().await;
+            Mock::given(method("POST"))
+                .and(path("/creatives"))
+                .respond_with(ResponseTemplate::new(422).set_body_json(
+                    json!({ "error": { "message": "invalid", "status_code": 422 } }),
+                ))
+                .expect(1)
+                .mount(&server)
+                .await;
+
+            let client = Client::builder("test_key")
+                .base_url(server.uri())
+                .build()
+                .unwrap();
+            let creative = NewCreative {
+                campaign_id: "cmp_123".parse().unwrap(),
+                resource_type: CreativeResourceType::Postcard,
+                from: SendAddress::AddressId("adr_from".parse().unwrap()),
+                description: None,
+                front: Some(FileInput::File {
+                    filename: "front.pdf".into(),
+                    data: b"front-bytes".to_vec(),
+                }),
+                back: Some(FileInput::Html("<h1>Back</h1>".into())),
+                file: None,
+                details: None,
+                metadata: None,
+            };
+            assert!(client.create_creative(&creative).await.is_err());
+
+            let requests = server.received_requests().await.unwrap();
+            let body = String::from_utf8_lossy(&requests[0].body);
+            assert!(body.contains(r#"name="front"; filename="front.pdf""#));
+            assert!(body.contains("front-bytes"));
+            assert!(body.contains("<h1>Back</h1>"));
+            assert!(!body.contains(r#"name="file""#));
+        })
+    }
+
+    #[test]
+    fn creates_self_mailers() {
+        block_on(async {
+            let server = MockServer::start().await;
+            Mock::given(method("POST"))
+                .and(path("/self_mailers"))
+                .and(body_json(json!({
+                    "description": null,
+                    "to": "adr_to",
+                    "from": null,
+                    "inside": "<html>inside</html>",
+                    "outside": "tmpl_outside",
+                    "merge_variables": null,
+                    "size": "6x18_bifold",
+                    "mail_type": "usps_standard",
+                    "use_type": "marketing",
+                    "send_date": null,
+                    "metadata": null,
+                })))
+                .respond_with(
+                    ResponseTemplate::new(200).set_body_json(crate::fixtures::self_mailer("sfm_123")),
+                )
+                .expect(1)
+                .mount(&server)
+                .await;
+
+            let client = Client::builder("test_key")
+                .base_url(server.uri())
+                .build()
+                .unwrap();
+            let self_mailer = client
+                .create_self_mailer(NewSelfMailer {
+                    description: None,
+                    to: SendAddress::AddressId("adr_to".parse().unwrap()),
+                    from: None,
+                    inside: FileInput::Html("<html>inside</html>".to_owned()),
+                    outside: FileInput::TemplateId("tmpl_outside".parse().unwrap()),
+                    merge_variables: None,
+                    size: Some(SelfMailerSize::SixByEighteenBifold),
+                    mail_type: Some(MailType::UspsStandard),
+                    use_type: Some(UseType::Marketing),
+                    send_date: None,
+                    metadata: None,
+                })
+                .await
+                .unwrap();
+            assert_eq!(self_mailer.id, "sfm_123");
+            assert_eq!(self_mailer.size, SelfMailerSize::SixByEighteenBifold);
+        })
+    }
+
+    #[test]
+    fn validates_requests_before_sending_them() {
+        block_on(async {
+            let server = MockServer::start().await;
+            Mock::given(wiremock::matchers::any())
+                .respond_with(ResponseTemplate::new(200))
+                .expect(0)
+                .mount(&server)
+                .await;
+
+            let client = Client::builder("test_key")
+                .base_url(server.uri())
+                .validate_requests(true)
+                .build()
+                .unwrap();
+            let err = client
+                .create_letter(NewLetter {
+                    perforated_page: Some(1),
+                    mail_type: Some(MailType::UpsNextDayAir),
+                    ..letter()
+                })
+                .await
+                .unwrap_err();
+            assert!(err.is_validation());
+            match err.kind() {
+                ErrorKind::Validation(err) => assert_eq!(err.violations().len(), 2),
+                kind => panic!("expected a validation error, got {:?}", kind),
+            }
+
+            let err = client
+                .create_self_mailer(NewSelfMailer {
+                    description: None,
+                    to: SendAddress::AddressId("adr_to".parse().unwrap()),
+                    from: None,
+                    inside: FileInput::Html("<html>inside</html>".to_owned()),
+                    outside: FileInput::TemplateId("tmpl_outside".parse().unwrap()),
+                    merge_variables: None,
+                    size: None,
+                    mail_type: Some(MailType::UpsNextDayAir),
+                    use_type: None,
+                    send_date: None,
+                    metadata: None,
+                })
+                .await
+                .unwrap_err();
+            assert!(err.is_validation());
+        })
+    }
+
+    #[test]
+    fn chunks_bulk_verifications_in_order() {
+        block_on(async {
+            let server = MockServer::start().await;
+            Mock::given(method("POST"))
+                .and(path("/bulk/intl_verifications"))
+                .respond_with(|request: &wiremock::Request| {
+                    let body: serde_json::Value = request.body_json().unwrap();
+                    let addresses = body["addresses"]
+                        .as_array()
+                        .unwrap()
+                        .iter()
+                        .map(|address| match address["primary_line"].as_str().unwrap() {
+                            "nowhere" => json!({
+                                "error": { "message": "primary_line is invalid", "status_code": 422 }
+                            }),
+                            line => crate::fixtures::intl_verification(line),
+                        })
+                        .collect::<Vec<_>>();
+                    ResponseTemplate::new(200).set_body_json(json!({ "addresses": addresses }))
+                })
+                .expect(2)
+                .mount(&server)
+                .await;
+
+            let client = Client::builder("test_key")
+                .base_url(server.uri())
+                .build()
+                .unwrap();
+            let addresses: Vec<_> = (0..MAX_BULK_VERIFICATIONS + 5)
+                .map(|i| InternationalVerificationInput {
+                    recipient: None,
+                    primary_line: if i == 21 {
+                        "nowhere".to_owned()
+                    } else {
+                        format!("{} Queen St W", i)
+                    },
+                    secondary_line: None,
+                    city: Some("Toronto".to_owned()),
+                    state: None,
+                    postal_code: None,
+                    country: "CA".to_owned(),
+                })
+                .collect();
+            let results = client.bulk_verify_intl_addresses(&addresses).await.unwrap();
+            assert_eq!(results.len(), addresses.len());
+            for (i, result) in results.iter().enumerate() {
+                match result {
+                    Ok(verification) => assert_eq!(verification.primary_line, addresses[i].primary_line),
+                    Err(error) => {
+                        assert_eq!(i, 21);
+                        assert_eq!(error.status_code, 422);
+                    }
+                }
+            }
+            assert!(results[21].is_err());
+            assert!(client.bulk_verify_intl_addresses(&[]).await.unwrap().is_empty());
+        })
+    }
+
+    #[test]
+    fn rejects_bulk_verifications_missing_results() {
+        block_on(async {
+            let server = MockServer::start().await;
+            Mock::given(method("POST"))
+                .and(path("/bulk/intl_verifications"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                    "addresses": [crate::fixtures::intl_verification("1 Queen St W")]
+                })))
+                .expect(1)
+                .mount(&server)
+                .await;
+
+            let client = Client::builder("test_key")
+                .base_url(server.uri())
+                .build()
+                .unwrap();
+            let addresses: Vec<_> = ["1 Queen St W", "2 Queen St W"]
+                .iter()
+                .map(|line| InternationalVerificationInput {
+                    recipient: None,
+                    primary_line: line.to_string(),
+                    secondary_line: None,
+                    city: Some("Toronto".to_owned()),
+                    state: None,
+                    postal_code: None,
+                    country: "CA".to_owned(),
+                })
+                .collect();
+            let err = client.bulk_verify_intl_addresses(&addresses).await.unwrap_err();
+            match err.kind() {
+                ErrorKind::Decode { path, source } => {
+                    assert_eq!(path, "addresses");
+                    assert_eq!(source.to_string(), "expected 2 results, got 1");
+                }
+                kind => panic!("unexpected error {:?}", kind),
+            }
+            // The batch was already verified, and billed, so it must not be sent again
+            assert_eq!(err.status(), None);
+            assert!(!err.is_retryable());
+        })
+    }
+
+    #[test]
+    fn sends_flat_and_component_addresses_for_bulk_verification() {
+        let addresses = [
+            "185 Berry St, San Francisco CA 94107".into_input(),
+            AddressVerificationComponents {
+                recipient: None,
+                primary_line: "185 Berry St".to_owned(),
+                secondary_line: None,
+                urbanization: None,
+                city: None,
+                state: None,
+                zip_code: Some("94107".to_owned()),
+            }
+            .into_input(),
+        ];
+        assert_eq!(
+            serde_json::to_value(BulkVerificationBody { addresses: &addresses }).unwrap(),
+            json!({
+                "addresses": [
+                    { "address": "185 Berry St, San Francisco CA 94107" },
+                    {
+                        "recipient": null,
+                        "primary_line": "185 Berry St",
+                        "secondary_line": null,
+                        "urbanization": null,
+                        "city": null,
+                        "state": null,
+                        "zip_code": "94107",
+                    },
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn uploads_audiences_and_sends_campaigns() {
+        block_on(async {
+            let server = MockServer::start().await;
+            let upload = crate::fixtures::upload("upl_123", "cmp_123", "Scheduled");
+            Mock::given(method("POST"))
+                .and(path("/uploads/upl_123/file"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                    "message": "File uploaded successfully",
+                    "filename": "audience.csv",
+                })))
+                .expect(1)
+                .mount(&server)
+                .await;
+            Mock::given(method("GET"))
+                .and(path("/uploads"))
+                .and(query_param("campaignId", "cmp_123"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(json!([upload])))
+                .expect(1)
+                .mount(&server)
+                .await;
+            Mock::given(method("POST"))
+                .and(path("/uploads/upl_123/exports"))
+                .and(body_json(json!({ "type": "failures" })))
+                .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                    "message": "Export is processing",
+                    "exportId": "ex_123",
+                })))
+                .expect(1)
+                .mount(&server)
+                .await;
+            Mock::given(method("GET"))
+                .and(path("/uploads/upl_123/exports/ex_123"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                    "id": "ex_123",
+                    "uploadId": "upl_123",
+                    "type": "failures",
+                    "state": "succeeded",
+                    "s3Url": "https://lob-exports.s3.amazonaws.com/ex_123.csv",
+                    "dateCreated": "2020-02-11T00:00:00.000Z",
+                    "dateModified": "2020-02-11T00:00:00.000Z",
+                })))
+                .expect(1)
+                .mount(&server)
+                .await;
+            Mock::given(method("POST"))
+                .and(path("/campaigns/cmp_123/send"))
+                .respond_with(
+                    ResponseTemplate::new(200)
+                        .set_body_json(crate::fixtures::campaign("cmp_123", false)),
+                )
+                .expect(1)
+                .mount(&server)
+                .await;
+
+            let client = Client::builder("test_key")
+                .base_url(server.uri())
+                .build()
+                .unwrap();
+            let upload_id = "upl_123".parse().unwrap();
+            let campaign_id = "cmp_123".parse().unwrap();
+            let csv = b"recipient,street,city,state,zip,first\n".to_vec();
+            let uploaded = client
+                .upload_file(&upload_id, "audience.csv", csv)
+                .await
+                .unwrap();
+            assert_eq!(uploaded.filename, "audience.csv");
+            let requests = server.received_requests().await.unwrap();
+            let body = String::from_utf8_lossy(&requests[0].body);
+            assert!(body.contains(r#"name="file"; filename="audience.csv""#));
+            assert!(body.contains("recipient,street,city,state,zip,first"));
+
+            let uploads = client.list_uploads(Some(&campaign_id)).await.unwrap();
+            assert_eq!(uploads[0].state, UploadState::Scheduled);
+            assert_eq!(uploads[0].failed_mailpieces, Some(1));
+
+            let export = client
+                .create_upload_export(&upload_id, ExportType::Failures)
+                .await
+                .unwrap();
+            let export = client
+                .get_upload_export(&upload_id, &export.export_id)
+                .await
+                .unwrap();
+            assert_eq!(export.state, ExportState::Succeeded);
+            assert!(export.s3_url.is_some());
+
+            let campaign = client.send_campaign(&campaign_id).await.unwrap();
+            assert!(!campaign.is_draft);
+        })
+    }
+
+    #[test]
+    fn overrides_api_version() {
+        block_on(async {
+            let server = MockServer::start().await;
+            Mock::given(method("DELETE"))
+                .and(header("Lob-Version", "2024-01-01"))
+                .respond_with(
+                    ResponseTemplate::new(200)
+                        .set_body_json(json!({ "id": "ltr_123", "deleted": true })),
+                )
+                .expect(1)
+                .mount(&server)
+                .await;
+
+            let client = Client::builder("test_key")
+                .base_url(server.uri())
+                .api_version("2024-01-01")
+                .build()
+                .unwrap();
+            assert_eq!(client.api_version(), "2024-01-01");
+            assert!(client.cancel_letter(&"ltr_123".parse().unwrap()).await.unwrap().deleted);
+        })
+    }
+
+    #[test]
+    fn exposes_error_details() {
+        block_on(async {
+            let server = MockServer::start().await;
+            let body = json!({
+                "error": {
+                    "message": "postcard not found",
+                    "status_code": 404,
+                    "code": "not_found"
+                }
+            });
+            Mock::given(method("GET"))
+                .respond_with(ResponseTemplate::new(404).set_body_json(&body))
+                .mount(&server)
+                .await;
+
+            let client = Client::builder("test_key")
+                .base_url(server.uri())
+                .build()
+                .unwrap();
+            let err = client.get_postcard(&"psc_123".parse().unwrap()).await.unwrap_err();
+            assert!(err.is_not_found());
+            assert_eq!(err.status(), Some(404));
+            assert_eq!(err.lob_code(), Some("not_found"));
+            assert_eq!(err.body(), Some(body.to_string().as_str()));
+            assert!(matches!(err.kind(), ErrorKind::Lob(e) if e.message == "postcard not found"));
+        })
+    }
+
+    #[test]
+    fn preserves_non_json_error_responses() {
+        block_on(async {
+            let server = MockServer::start().await;
+            Mock::given(method("GET"))
+                .respond_with(
+                    ResponseTemplate::new(502)
+                        .set_body_raw("<html>Bad Gateway</html>", "text/html"),
+                )
+                .mount(&server)
+                .await;
+
+            let client = Client::builder("test_key")
+                .base_url(server.uri())
+                .build()
+                .unwrap();
+            let err = client.get_letter(&"ltr_123".parse().unwrap()).await.unwrap_err();
+            assert_eq!(err.status(), Some(502));
+            assert!(err.is_retryable());
+            match err.kind() {
+                ErrorKind::UnexpectedResponse {
+                    content_type, body, ..
+                } => {
+                    assert_eq!(content_type.as_deref(), Some("text/html"));
+                    assert_eq!(body, "<html>Bad Gateway</html>");
+                }
+                kind => panic!("unexpected kind {:?}", kind),
+            }
+        })
+    }
+
+    #[test]
+    fn reports_path_of_undecodable_field() {
+        block_on(async {
+            let server = MockServer::start().await;
+            Mock::given(method("DELETE"))
+                .respond_with(
+                    ResponseTemplate::new(200)
+                        .set_body_json(json!({ "id": "adr_123", "deleted": "yes" })),
+                )
+                .mount(&server)
+                .await;
+
+            let client = Client::builder("test_key")
+                .base_url(server.uri())
+                .build()
+                .unwrap();
+            let err = client.delete_address(&"adr_123".parse().unwrap()).await.unwrap_err();
+            match err.kind() {
+                ErrorKind::Decode { path, .. } => assert_eq!(path, "deleted"),
+                kind => panic!("unexpected kind {:?}", kind),
+            }
+        })
+    }
+
+    #[test]
+    fn lists_and_gets_events() {
+        block_on(async {
+            let server = MockServer::start().await;
+            let event = crate::fixtures::event(
+                "evt_123",
+                "address.created",
+                "addresses",
+                crate::fixtures::address("adr_123"),
+            );
+            Mock::given(method("GET"))
+                .and(path("/events"))
+                .and(query_param("event_type_id", "letter.returned_to_sender"))
+                .and(query_param("limit", "5"))
+                .respond_with(
+                    ResponseTemplate::new(200)
+                        .set_body_json(crate::fixtures::list(vec![event.clone()], None)),
+                )
+                .expect(1)
+                .mount(&server)
+                .await;
+            Mock::given(method("GET"))
+                .and(path("/events/evt_123"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(&event))
+                .expect(1)
+                .mount(&server)
+                .await;
+
+            let client = Client::builder("test_key")
+                .base_url(server.uri())
+                .build()
+                .unwrap();
+            let events = client
+                .list_events(Some(ListEventOptions {
+                    limit: Some(5),
+                    event_type_id: Some(EventTypeId::LetterReturnedToSender),
+                    ..ListEventOptions::default()
+                }))
+                .await
+                .unwrap();
+            assert_eq!(events.data[0].id, "evt_123");
+
+            let event = client.get_event(&"evt_123".parse().unwrap()).await.unwrap();
+            assert_eq!(event.event_type.id, EventTypeId::AddressCreated);
+            assert_eq!(event.event_type.resource, Resource::Addresses);
+            assert_eq!(event.as_address().unwrap().id, "adr_123");
+        })
+    }
+
+    #[test]
+    fn creates_and_publishes_template_versions() {
+        block_on(async {
+            let server = MockServer::start().await;
+            let html = include_str!("../postcard_front.html");
+            Mock::given(method("POST"))
+                .and(path("/templates/tmpl_123/versions"))
+                .and(body_json(json!({
+                    "description": "spring campaign",
+                    "html": html,
+                    "engine": "handlebars",
+                })))
+                .respond_with(
+                    ResponseTemplate::new(200)
+                        .set_body_json(&crate::fixtures::template("tmpl_123", "vrsn_456")["published_version"]),
+                )
+                .expect(1)
+                .mount(&server)
+                .await;
+            Mock::given(method("POST"))
+                .and(path("/templates/tmpl_123"))
+                .and(body_json(json!({ "published_version": "vrsn_456" })))
+                .respond_with(
+                    ResponseTemplate::new(200)
+                        .set_body_json(crate::fixtures::template("tmpl_123", "vrsn_456")),
+                )
+                .expect(1)
+                .mount(&server)
+                .await;
+
+            let client = Client::builder("test_key")
+                .base_url(server.uri())
+                .build()
+                .unwrap();
+            let template_id = "tmpl_123".parse().unwrap();
+            let version = client
+                .create_template_version(
+                    &template_id,
+                    &NewTemplateVersion {
+                        description: Some("spring campaign".to_owned()),
+                        html: html.to_owned(),
+                        engine: Some(TemplateEngine::Handlebars),
+                    },
+                )
+                .await
+                .unwrap();
+            assert_eq!(version.merge_variables.unwrap().keys, ["name"]);
+
+            let template = client
+                .publish_template_version(&template_id, &version.id)
+                .await
+                .unwrap();
+            assert_eq!(template.published_version.id, version.id);
+        })
+    }
+
+    #[test]
+    fn connect_timeout_requires_owned_http_client() {
+        let result = Client::builder("test_key")
+            .http_client(reqwest::Client::new())
+            .connect_timeout(Duration::from_secs(1))
+            .build();
+        assert!(result.is_err());
+    }
+}
@@ -1,43 +1,190 @@
 use crate::model::LobError;
+use crate::validate::ValidationError;
 use std::fmt;
+use std::time::Duration;
+
+/// How much of an error response body is kept, in `Error::body` and in
+/// `ErrorKind::UnexpectedResponse`.
+const MAX_UNEXPECTED_BODY_LEN: usize = 1024;
 
 #[derive(Debug)]
 pub struct Error {
     kind: ErrorKind,
+    status: Option<u16>,
+    body: Option<String>,
+    retry_after: Option<Duration>,
 }
 
 impl Error {
-    pub fn bad_request<S: Into<String>>(msg: S) -> Error {
+    fn new(kind: ErrorKind) -> Error {
         Error {
-            kind: ErrorKind::BadRequest(msg.into()),
+            kind,
+            status: None,
+            body: None,
+            retry_after: None,
         }
     }
 
+    pub fn bad_request<S: Into<String>>(msg: S) -> Error {
+        Error::new(ErrorKind::BadRequest(msg.into()))
+    }
+
+    pub fn kind(&self) -> &ErrorKind {
+        &self.kind
+    }
+
+    pub fn into_kind(self) -> ErrorKind {
+        self.kind
+    }
+
+    /// The HTTP status code of the response, if one was received.
+    pub fn status(&self) -> Option<u16> {
+        self.status.or(match &self.kind {
+            ErrorKind::Lob(e) => u16::try_from(e.status_code).ok(),
+            ErrorKind::Http(e) => e.status().map(|s| s.as_u16()),
+            ErrorKind::UnexpectedResponse { status, .. } => Some(*status),
+            ErrorKind::Serde(_)
+            | ErrorKind::Decode { .. }
+            | ErrorKind::BadRequest(_)
+            | ErrorKind::Cassette(_)
+            | ErrorKind::Validation(_) => None,
+        })
+    }
+
+    /// Lob's machine readable error code, e.g. `failed_deliverability_strictness`.
+    pub fn lob_code(&self) -> Option<&str> {
+        match &self.kind {
+            ErrorKind::Lob(e) => e.code.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// The raw body of the error response, if one was received, cut to its first 1024 bytes.
+    pub fn body(&self) -> Option<&str> {
+        self.body.as_deref()
+    }
+
+    pub fn is_not_found(&self) -> bool {
+        self.status() == Some(404)
+    }
+
+    pub fn is_rate_limited(&self) -> bool {
+        self.status() == Some(429)
+    }
+
+    /// The API key is missing, invalid or not permitted to perform the request.
+    pub fn is_auth(&self) -> bool {
+        matches!(self.status(), Some(401) | Some(403))
+    }
+
+    /// The request was rejected as invalid, either by Lob or before it was sent.
+    pub fn is_validation(&self) -> bool {
+        matches!(self.kind, ErrorKind::BadRequest(_) | ErrorKind::Validation(_))
+            || matches!(self.status(), Some(400) | Some(422))
+    }
+
     // Do we expect retrying the same request to ever succeed
     pub fn is_retryable(&self) -> bool {
-        match &self.kind {
-            ErrorKind::Lob(e) => !(e.status_code >= 400 && e.status_code < 500),
-            ErrorKind::Http(e) => e.status().map_or(true, |c| c.as_u16() != 400),
-            ErrorKind::Serde(_) | ErrorKind::BadRequest(_) => false,
+        match (self.status(), &self.kind) {
+            (Some(status), _) => status == 429 || !(400..500).contains(&status),
+            (None, ErrorKind::Http(_)) => true,
+            (None, _) => false,
         }
     }
+
+    /// How long the server asked us to wait before retrying, from the `Retry-After` header.
+    pub fn retry_after(&self) -> Option<Duration> {
+        self.retry_after
+    }
+
+    pub(crate) fn cassette<S: Into<String>>(msg: S) -> Error {
+        Error::new(ErrorKind::Cassette(msg.into()))
+    }
+
+    pub(crate) fn unexpected_response(
+        status: u16,
+        content_type: Option<String>,
+        body: &str,
+    ) -> Error {
+        Error::new(ErrorKind::UnexpectedResponse {
+            status,
+            content_type,
+            body: truncate(body).to_owned(),
+        })
+    }
+
+    /// A successful response that is valid JSON, but not what `path` should hold.
+    pub(crate) fn decode<M: fmt::Display>(path: &str, message: M) -> Error {
+        Error::new(ErrorKind::Decode {
+            path: path.to_owned(),
+            source: serde::de::Error::custom(message),
+        })
+    }
+
+    pub(crate) fn with_response(mut self, status: u16, mut body: String) -> Error {
+        body.truncate(truncate(&body).len());
+        self.status = Some(status);
+        self.body = Some(body);
+        self
+    }
+
+    pub(crate) fn with_retry_after(mut self, retry_after: Option<Duration>) -> Error {
+        self.retry_after = retry_after;
+        self
+    }
 }
 
 #[derive(Debug)]
-enum ErrorKind {
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// Lob rejected the request, see `LobError` for the details it returned.
     Lob(LobError),
+    /// The request could not be sent or the response could not be read.
     Http(reqwest::Error),
     Serde(SerdeError),
+    /// A successful response did not match the expected model; `path` locates the failing field,
+    /// e.g. `data[3].to.address_city`.
+    Decode {
+        path: String,
+        source: serde_json::Error,
+    },
+    /// An error response that is not one of Lob's JSON errors, e.g. an HTML page from a proxy.
+    /// `body` is truncated to its first 1024 bytes.
+    UnexpectedResponse {
+        status: u16,
+        content_type: Option<String>,
+        body: String,
+    },
+    /// The request was rejected before it was sent.
     BadRequest(String),
+    /// A replayed `Cassette` has no recorded response for the request.
+    Cassette(String),
+    /// The request was not sent because it breaks limits Lob enforces, see
+    /// `ClientBuilder::validate_requests`.
+    Validation(ValidationError),
 }
 
 #[derive(Debug)]
-enum SerdeError {
+pub enum SerdeError {
     Json(serde_json::Error),
     Qs(serde_qs::Error),
 }
 
-impl std::error::Error for Error {}
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.kind {
+            ErrorKind::Http(err) => Some(err),
+            ErrorKind::Serde(SerdeError::Json(err)) => Some(err),
+            ErrorKind::Serde(SerdeError::Qs(err)) => Some(err),
+            ErrorKind::Decode { source, .. } => Some(source),
+            ErrorKind::Validation(err) => Some(err),
+            ErrorKind::Lob(_)
+            | ErrorKind::UnexpectedResponse { .. }
+            | ErrorKind::BadRequest(_)
+            | ErrorKind::Cassette(_) => None,
+        }
+    }
+}
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -45,49 +192,153 @@ impl fmt::Display for Error {
             ErrorKind::Lob(LobError {
                 message,
                 status_code,
+                code: Some(code),
+            }) => write!(
+                f,
+                "Lob error - status_code: {}, code: {}, message: {}",
+                status_code, code, message
+            ),
+            ErrorKind::Lob(LobError {
+                message,
+                status_code,
+                code: None,
             }) => write!(
                 f,
                 "Lob error - status_code: {}, message: {}",
-                message, status_code
+                status_code, message
             ),
             ErrorKind::Http(err) => write!(f, "Lob error (reqwest) - {}", err),
             ErrorKind::Serde(SerdeError::Json(err)) => {
                 write!(f, "Lob error (serde_json) - {}", err)
             }
             ErrorKind::Serde(SerdeError::Qs(err)) => write!(f, "Lob error (serde_qs) - {}", err),
+            ErrorKind::Decode { path, source } => {
+                write!(f, "Lob error (decode) - at `{}`: {}", path, source)
+            }
+            ErrorKind::UnexpectedResponse {
+                status,
+                content_type,
+                body,
+            } => write!(
+                f,
+                "Lob error (unexpected response) - status: {}, content_type: {}, body: {}",
+                status,
+                content_type.as_deref().unwrap_or("none"),
+                body
+            ),
             ErrorKind::BadRequest(msg) => write!(f, "Lob error (bad request) - {}", msg),
+            ErrorKind::Cassette(msg) => write!(f, "Lob error (cassette) - {}", msg),
+            ErrorKind::Validation(err) => write!(f, "Lob error (validation) - {}", err),
         }
     }
 }
 
 impl From<reqwest::Error> for Error {
     fn from(err: reqwest::Error) -> Self {
-        Error {
-            kind: ErrorKind::Http(err),
-        }
+        Error::new(ErrorKind::Http(err))
     }
 }
 
 impl From<serde_json::Error> for Error {
     fn from(err: serde_json::Error) -> Self {
-        Error {
-            kind: ErrorKind::Serde(SerdeError::Json(err)),
-        }
+        Error::new(ErrorKind::Serde(SerdeError::Json(err)))
     }
 }
 
 impl From<serde_qs::Error> for Error {
     fn from(err: serde_qs::Error) -> Self {
-        Error {
-            kind: ErrorKind::Serde(SerdeError::Qs(err)),
-        }
+        Error::new(ErrorKind::Serde(SerdeError::Qs(err)))
+    }
+}
+
+impl From<serde_path_to_error::Error<serde_json::Error>> for Error {
+    fn from(err: serde_path_to_error::Error<serde_json::Error>) -> Self {
+        Error::new(ErrorKind::Decode {
+            path: err.path().to_string(),
+            source: err.into_inner(),
+        })
+    }
+}
+
+impl From<ValidationError> for Error {
+    fn from(err: ValidationError) -> Self {
+        Error::new(ErrorKind::Validation(err))
     }
 }
 
 impl From<LobError> for Error {
     fn from(err: LobError) -> Self {
-        Error {
-            kind: ErrorKind::Lob(err),
+        Error::new(ErrorKind::Lob(err))
+    }
+}
+
+/// The first `MAX_UNEXPECTED_BODY_LEN` bytes of `body`, or fewer to end on a character.
+fn truncate(body: &str) -> &str {
+    let mut end = body.len().min(MAX_UNEXPECTED_BODY_LEN);
+    while !body.is_char_boundary(end) {
+        end -= 1;
+    }
+    &body[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lob_error(status_code: i32, code: Option<&str>) -> Error {
+        LobError {
+            message: "something went wrong".to_owned(),
+            status_code,
+            code: code.map(str::to_owned),
         }
+        .into()
+    }
+
+    #[test]
+    fn classifies_lob_errors() {
+        let err = lob_error(404, Some("not_found"));
+        assert_eq!(err.status(), Some(404));
+        assert_eq!(err.lob_code(), Some("not_found"));
+        assert!(err.is_not_found());
+        assert!(!err.is_retryable());
+
+        assert!(lob_error(429, None).is_rate_limited());
+        assert!(lob_error(429, None).is_retryable());
+        assert!(lob_error(401, None).is_auth());
+        assert!(lob_error(403, None).is_auth());
+        assert!(lob_error(422, None).is_validation());
+        assert!(lob_error(500, None).is_retryable());
+        assert!(Error::bad_request("invalid").is_validation());
+    }
+
+    #[test]
+    fn truncates_unexpected_bodies() {
+        let body = "é".repeat(MAX_UNEXPECTED_BODY_LEN);
+        let err = Error::unexpected_response(502, Some("text/html".to_owned()), &body);
+        assert_eq!(err.status(), Some(502));
+        assert!(err.is_retryable());
+        match err.kind() {
+            ErrorKind::UnexpectedResponse { body, .. } => {
+                assert!(body.len() <= MAX_UNEXPECTED_BODY_LEN);
+                assert!(body.chars().all(|c| c == 'é'));
+            }
+            kind => panic!("unexpected kind {:?}", kind),
+        }
+
+        let err = lob_error(500, None).with_response(500, body);
+        assert!(err.body().unwrap().len() <= MAX_UNEXPECTED_BODY_LEN);
+        assert!(err.body().unwrap().starts_with('é'));
+    }
+
+    #[test]
+    fn displays_status_before_message() {
+        assert_eq!(
+            lob_error(422, Some("invalid")).to_string(),
+            "Lob error - status_code: 422, code: invalid, message: something went wrong"
+        );
+        assert_eq!(
+            lob_error(500, None).to_string(),
+            "Lob error - status_code: 500, message: something went wrong"
+        );
     }
 }
@@ -0,0 +1,254 @@
+//! An in-memory `LobApi` for unit tests of code that sends mail.
+//!
+//! `FakeLob` never talks to Lob. Every call is recorded with its arguments serialized to JSON,
+//! and answered with the replies configured for its method, through the `on_*` method of the
+//! same name, e.g. `on_get_letter` for `get_letter`:
+//!
+//! ```
+//! use lob::fake::FakeLob;
+//! use lob::model::LobError;
+//! use lob::LobApi;
+//!
+//! # tokio_test::block_on(async {
+//! let lob = FakeLob::new();
+//! lob.on_get_letter().fail(LobError {
+//!     message: "letter not found".to_owned(),
+//!     status_code: 404,
+//!     code: Some("not_found".to_owned()),
+//! });
+//!
+//! let err = lob.get_letter(&"ltr_4868c3b754655f90".parse().unwrap()).await.unwrap_err();
+//! assert!(err.is_not_found());
+//! assert_eq!(lob.calls()[0].args["letter_id"], "ltr_4868c3b754655f90");
+//! # })
+//! ```
+//!
+//! Replies have the type their method returns, so answering `get_letter` with anything but a
+//! `Letter` does not compile:
+//!
+//! ```compile_fail
+//! # use lob::fake::FakeLob;
+//! # use lob::model::Delete;
+//! # let deleted: Delete = serde_json::from_str(r#"{"id": "ltr_1", "deleted": true}"#).unwrap();
+//! FakeLob::new().on_get_letter().respond(deleted);
+//! ```
+
+use crate::error::Error;
+use crate::model::LobError;
+use serde_json::Value;
+use std::any::Any;
+use std::collections::{HashMap, VecDeque};
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+/// A call made to a `FakeLob`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Call {
+    /// The name of the `LobApi` method, e.g. `create_letter`.
+    pub method: &'static str,
+    /// The arguments by name, e.g. `{"letter": {...}}` for `create_letter`.
+    pub args: Value,
+}
+
+#[derive(Default)]
+pub struct FakeLob {
+    state: Mutex<State>,
+}
+
+#[derive(Default)]
+struct State {
+    calls: Vec<Call>,
+    replies: HashMap<&'static str, VecDeque<Reply>>,
+}
+
+#[derive(Clone)]
+enum Reply {
+    Ok(Arc<dyn Fn() -> Box<dyn Any + Send> + Send + Sync>),
+    Err(Arc<dyn Fn() -> Error + Send + Sync>),
+}
+
+impl FakeLob {
+    pub fn new() -> FakeLob {
+        FakeLob::default()
+    }
+
+    /// Every call made so far, oldest first.
+    pub fn calls(&self) -> Vec<Call> {
+        self.state().calls.clone()
+    }
+
+    /// The calls made to `method` so far, oldest first.
+    pub fn calls_to(&self, method: &str) -> Vec<Call> {
+        self.state()
+            .calls
+            .iter()
+            .filter(|call| call.method == method)
+            .cloned()
+            .collect()
+    }
+
+    /// Forget the calls made so far, but keep the configured replies.
+    pub fn clear_calls(&self) {
+        self.state().calls.clear();
+    }
+
+    fn push(&self, method: &'static str, reply: Reply) {
+        self.state()
+            .replies
+            .entry(method)
+            .or_default()
+            .push_back(reply);
+    }
+
+    pub(crate) fn call<R: 'static>(&self, method: &'static str, args: Value) -> Result<R, Error> {
+        let reply = {
+            let mut state = self.state();
+            state.calls.push(Call { method, args });
+            let replies = state.replies.get_mut(method);
+            match replies {
+                Some(replies) if replies.len() > 1 => replies.pop_front(),
+                Some(replies) => replies.front().cloned(),
+                None => None,
+            }
+        };
+        match reply {
+            Some(Reply::Ok(response)) => Ok(*response()
+                .downcast::<R>()
+                .expect("replies have the type their method returns")),
+            Some(Reply::Err(error)) => Err(error()),
+            None => panic!(
+                "`FakeLob::{}` was called without a reply configured",
+                method
+            ),
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// The replies of one `FakeLob` method, from its `on_*` method. `T` is the type the method
+/// returns inside its `Result`, e.g. a `Letter` for `create_letter`.
+///
+/// Replies are used once each, in the order they were configured, except for the last one,
+/// which answers every call after it.
+pub struct Replies<'a, T> {
+    fake: &'a FakeLob,
+    method: &'static str,
+    response: PhantomData<fn() -> T>,
+}
+
+impl<'a, T> Replies<'a, T> {
+    pub(crate) fn new(fake: &'a FakeLob, method: &'static str) -> Replies<'a, T> {
+        Replies {
+            fake,
+            method,
+            response: PhantomData,
+        }
+    }
+
+    /// Answer a call with `response`.
+    pub fn respond(self, response: T) -> Replies<'a, T>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        self.fake.push(
+            self.method,
+            Reply::Ok(Arc::new(move || Box::new(response.clone()))),
+        );
+        self
+    }
+
+    /// Fail a call as though Lob had rejected it with `error`.
+    pub fn fail(self, error: LobError) -> Replies<'a, T> {
+        self.fail_with(move || Error::from(error.clone()))
+    }
+
+    /// Fail a call with the error `error` returns, e.g. to simulate errors that are not Lob's.
+    pub fn fail_with<F>(self, error: F) -> Replies<'a, T>
+    where
+        F: Fn() -> Error + Send + Sync + 'static,
+    {
+        self.fake.push(self.method, Reply::Err(Arc::new(error)));
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::LobApi;
+    use crate::fixtures;
+    use crate::id::LetterId;
+    use crate::model::*;
+    use futures::TryStreamExt;
+    use serde_json::json;
+    use std::sync::Arc;
+    use tokio_test::block_on;
+
+    /// Code under test only knows about `LobApi`.
+    async fn cancel_all(lob: Arc<dyn LobApi>) -> Result<usize, crate::Error> {
+        let letters: Vec<Letter> = lob.list_letters_stream(None).try_collect().await?;
+        for letter in &letters {
+            lob.cancel_letter(&letter.id).await?;
+        }
+        Ok(letters.len())
+    }
+
+    fn letter(id: &str) -> Letter {
+        serde_json::from_value(fixtures::letter(id)).unwrap()
+    }
+
+    fn deleted(id: &str) -> Delete {
+        serde_json::from_value(json!({ "id": id, "deleted": true })).unwrap()
+    }
+
+    #[test]
+    fn records_calls_and_replies_in_order() {
+        block_on(async {
+            let fake = Arc::new(FakeLob::new());
+            let page = serde_json::from_value(fixtures::list(
+                vec![fixtures::letter("ltr_1"), fixtures::letter("ltr_2")],
+                None,
+            ))
+            .unwrap();
+            fake.on_list_letters().respond(page);
+            fake.on_cancel_letter()
+                .respond(deleted("ltr_1"))
+                .fail(LobError {
+                    message: "letter has already been sent".to_owned(),
+                    status_code: 422,
+                    code: Some("invalid".to_owned()),
+                });
+
+            let err = cancel_all(fake.clone()).await.unwrap_err();
+            assert_eq!(err.lob_code(), Some("invalid"));
+
+            let calls = fake.calls();
+            assert_eq!(calls.len(), 3);
+            assert_eq!(calls[0].method, "list_letters");
+            assert!(calls[0].args["options"].is_object());
+            let canceled: Vec<_> = fake
+                .calls_to("cancel_letter")
+                .into_iter()
+                .map(|call| call.args["letter_id"].clone())
+                .collect();
+            assert_eq!(canceled, vec![json!("ltr_1"), json!("ltr_2")]);
+
+            // The last reply keeps answering
+            let id: LetterId = "ltr_3".parse().unwrap();
+            assert!(fake.cancel_letter(&id).await.is_err());
+            fake.clear_calls();
+            assert!(fake.calls().is_empty());
+        })
+    }
+
+    #[test]
+    #[should_panic(expected = "`FakeLob::get_address` was called without a reply configured")]
+    fn panics_on_calls_without_replies() {
+        let fake = FakeLob::new();
+        fake.on_get_letter().respond(letter("ltr_1"));
+        let _ = block_on(fake.get_address(&"adr_1".parse().unwrap()));
+    }
+}
@@ -0,0 +1,220 @@
+//! Response bodies shaped like Lob's, shared by the tests of several modules.
+
+use serde_json::{json, Value};
+
+pub fn address(id: &str) -> Value {
+    json!({
+        "id": id,
+        "description": null,
+        "name": "Wyyerd Central",
+        "company": null,
+        "phone": null,
+        "email": null,
+        "address_line1": "5600 Arapahoe Ave. STE 200",
+        "address_line2": null,
+        "address_city": "Boulder",
+        "address_state": "CO",
+        "address_zip": "80304",
+        "address_country": "UNITED STATES",
+        "metadata": {},
+        "date_created": "2020-02-11T00:00:00.000Z",
+        "date_modified": "2020-02-11T00:00:00.000Z",
+        "object": "address"
+    })
+}
+
+pub fn event(id: &str, event_type: &str, resource: &str, body: Value) -> Value {
+    json!({
+        "id": id,
+        "body": body,
+        "reference_id": body["id"],
+        "event_type": {
+            "id": event_type,
+            "enabled_for_test": true,
+            "resource": resource,
+            "object": "event_type"
+        },
+        "date_created": "2020-02-11T00:00:00.000Z",
+        "object": "event"
+    })
+}
+
+pub fn list(data: Vec<Value>, next_url: Option<String>) -> Value {
+    json!({
+        "object": "list",
+        "count": data.len(),
+        "data": data,
+        "next_url": next_url,
+        "previous_url": null,
+    })
+}
+
+pub fn letter(id: &str) -> Value {
+    json!({
+        "id": id,
+        "description": null,
+        "metadata": {},
+        "to": address("adr_to"),
+        "from": address("adr_from"),
+        "color": false,
+        "double_sided": true,
+        "address_placement": "top_first_page",
+        "return_envelope": false,
+        "perforated_page": null,
+        "custom_envelope": null,
+        "extra_service": null,
+        "mail_type": "usps_first_class",
+        "url": "https://lob-assets.com/letters/ltr.pdf",
+        "merge_variables": null,
+        "template_id": null,
+        "template_version_id": null,
+        "carrier": "USPS",
+        "tracking_number": null,
+        "tracking_events": [],
+        "thumbnails": [],
+        "expected_delivery_date": "2020-02-17",
+        "date_created": "2020-02-11T00:00:00.000Z",
+        "date_modified": "2020-02-11T00:00:00.000Z",
+        "send_date": "2020-02-11T00:00:00.000Z",
+        "object": "letter"
+    })
+}
+
+pub fn postcard(id: &str) -> Value {
+    json!({
+        "id": id,
+        "description": null,
+        "metadata": {},
+        "to": address("adr_to"),
+        "from": address("adr_from"),
+        "url": "https://lob-assets.com/postcards/psc.pdf",
+        "front_template_id": null,
+        "back_template_id": null,
+        "front_template_version_id": null,
+        "back_template_version_id": null,
+        "carrier": "USPS",
+        "tracking_events": [],
+        "thumbnails": [],
+        "merge_variables": null,
+        "size": "4x6",
+        "mail_type": "usps_first_class",
+        "expected_delivery_date": "2020-02-17",
+        "date_created": "2020-02-11T00:00:00.000Z",
+        "date_modified": "2020-02-11T00:00:00.000Z",
+        "send_date": "2020-02-11T00:00:00.000Z",
+        "object": "postcard"
+    })
+}
+
+pub fn template(id: &str, version_id: &str) -> Value {
+    let version = json!({
+        "id": version_id,
+        "description": null,
+        "html": "<html>{{name}}</html>",
+        "engine": "handlebars",
+        "merge_variables": { "keys": ["name"] },
+        "date_created": "2020-02-11T00:00:00.000Z",
+        "date_modified": "2020-02-11T00:00:00.000Z",
+        "object": "version"
+    });
+    json!({
+        "id": id,
+        "description": "postcard front",
+        "versions": [version],
+        "published_version": version,
+        "metadata": {},
+        "date_created": "2020-02-11T00:00:00.000Z",
+        "date_modified": "2020-02-11T00:00:00.000Z",
+        "object": "template"
+    })
+}
+
+pub fn self_mailer(id: &str) -> Value {
+    json!({
+        "id": id,
+        "description": null,
+        "metadata": {},
+        "to": address("adr_to"),
+        "from": address("adr_from"),
+        "url": "https://lob-assets.com/self-mailers/sfm.pdf",
+        "inside_template_id": null,
+        "outside_template_id": null,
+        "inside_template_version_id": null,
+        "outside_template_version_id": null,
+        "tracking_events": [],
+        "merge_variables": null,
+        "size": "6x18_bifold",
+        "mail_type": "usps_standard",
+        "use_type": "marketing",
+        "expected_delivery_date": "2020-02-19",
+        "date_created": "2020-02-11T00:00:00.000Z",
+        "date_modified": "2020-02-11T00:00:00.000Z",
+        "send_date": "2020-02-11T00:00:00.000Z",
+        "object": "self_mailer"
+    })
+}
+
+pub fn intl_verification(primary_line: &str) -> Value {
+    json!({
+        "id": "intl_ver_123",
+        "recipient": "",
+        "primary_line": primary_line,
+        "secondary_line": "",
+        "last_line": "TORONTO ON M5V 2A2",
+        "country": "CA",
+        "deliverability": "deliverable",
+        "components": {
+            "primary_object": null,
+            "street_name": null,
+            "city": "TORONTO",
+            "state": "ON",
+            "postal_code": "M5V 2A2"
+        },
+        "object": "intl_verification"
+    })
+}
+
+pub fn campaign(id: &str, is_draft: bool) -> Value {
+    json!({
+        "id": id,
+        "name": "spring campaign",
+        "description": null,
+        "billing_group_id": null,
+        "schedule_type": "immediate",
+        "target_delivery_date": null,
+        "send_date": null,
+        "cancel_window_campaign_minutes": 60,
+        "use_type": "marketing",
+        "auto_cancel_if_ncoa": false,
+        "is_draft": is_draft,
+        "metadata": {},
+        "date_created": "2020-02-11T00:00:00.000Z",
+        "date_modified": "2020-02-11T00:00:00.000Z",
+        "object": "campaign"
+    })
+}
+
+pub fn upload(id: &str, campaign_id: &str, state: &str) -> Value {
+    json!({
+        "id": id,
+        "campaignId": campaign_id,
+        "state": state,
+        "originalFilename": "audience.csv",
+        "totalMailpieces": 2,
+        "validatedMailpieces": 1,
+        "failedMailpieces": 1,
+        "failuresUrl": null,
+        "requiredAddressColumnMapping": {
+            "name": "recipient",
+            "address_line1": "street",
+            "address_city": "city",
+            "address_state": "state",
+            "address_zip": "zip"
+        },
+        "optionalAddressColumnMapping": null,
+        "mergeVariableColumnMapping": { "first_name": "first" },
+        "metadata": {},
+        "dateCreated": "2020-02-11T00:00:00.000Z",
+        "dateModified": "2020-02-11T00:00:00.000Z"
+    })
+}
@@ -0,0 +1,174 @@
+//! Identifiers of Lob resources. Each one checks the prefix Lob gives IDs of its resource, so a
+//! letter ID cannot be passed where a postcard ID is expected.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+macro_rules! resource_id {
+    ($($(#[$meta:meta])* $name:ident = $prefix:literal,)*) => {
+        $(
+            $(#[$meta])*
+            #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+            pub struct $name(String);
+
+            impl $name {
+                pub const PREFIX: &'static str = $prefix;
+
+                pub fn as_str(&self) -> &str {
+                    &self.0
+                }
+
+                pub fn into_string(self) -> String {
+                    self.0
+                }
+            }
+
+            impl FromStr for $name {
+                type Err = ParseIdError;
+
+                fn from_str(id: &str) -> Result<Self, Self::Err> {
+                    $name::try_from(id.to_owned())
+                }
+            }
+
+            impl TryFrom<String> for $name {
+                type Error = ParseIdError;
+
+                fn try_from(id: String) -> Result<Self, Self::Error> {
+                    if id.len() > $prefix.len() && id.starts_with($prefix) {
+                        Ok($name(id))
+                    } else {
+                        Err(ParseIdError {
+                            resource: stringify!($name),
+                            prefix: $prefix,
+                            id,
+                        })
+                    }
+                }
+            }
+
+            impl TryFrom<&str> for $name {
+                type Error = ParseIdError;
+
+                fn try_from(id: &str) -> Result<Self, Self::Error> {
+                    id.parse()
+                }
+            }
+
+            impl From<$name> for String {
+                fn from(id: $name) -> String {
+                    id.0
+                }
+            }
+
+            impl AsRef<str> for $name {
+                fn as_ref(&self) -> &str {
+                    &self.0
+                }
+            }
+
+            impl PartialEq<str> for $name {
+                fn eq(&self, other: &str) -> bool {
+                    self.0 == other
+                }
+            }
+
+            impl PartialEq<&str> for $name {
+                fn eq(&self, other: &&str) -> bool {
+                    self.0 == *other
+                }
+            }
+
+            impl fmt::Display for $name {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    f.write_str(&self.0)
+                }
+            }
+
+            impl Serialize for $name {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    serializer.serialize_str(&self.0)
+                }
+            }
+
+            impl<'de> Deserialize<'de> for $name {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    let id = String::deserialize(deserializer)?;
+                    $name::try_from(id).map_err(serde::de::Error::custom)
+                }
+            }
+        )*
+    };
+}
+
+resource_id! {
+    AddressId = "adr_",
+    PostcardId = "psc_",
+    LetterId = "ltr_",
+    SelfMailerId = "sfm_",
+    CheckId = "chk_",
+    BankAccountId = "bank_",
+    EventId = "evt_",
+    TemplateId = "tmpl_",
+    TemplateVersionId = "vrsn_",
+    CampaignId = "cmp_",
+    CreativeId = "crv_",
+    UploadId = "upl_",
+    UploadExportId = "ex_",
+}
+
+/// An ID without the prefix of the resource it was parsed as.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseIdError {
+    resource: &'static str,
+    prefix: &'static str,
+    id: String,
+}
+
+impl ParseIdError {
+    /// The ID that failed to parse.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+impl std::error::Error for ParseIdError {}
+
+impl fmt::Display for ParseIdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid {} {:?}, expected an ID starting with {:?}",
+            self.resource, self.id, self.prefix
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_ids_with_their_prefix() {
+        let id: AddressId = "adr_d3489cd64c791ab5".parse().unwrap();
+        assert_eq!(id, "adr_d3489cd64c791ab5");
+        assert_eq!(id.to_string(), "adr_d3489cd64c791ab5");
+
+        let err = "ltr_d3489cd64c791ab5".parse::<PostcardId>().unwrap_err();
+        assert_eq!(err.id(), "ltr_d3489cd64c791ab5");
+        assert_eq!(
+            err.to_string(),
+            r#"invalid PostcardId "ltr_d3489cd64c791ab5", expected an ID starting with "psc_""#
+        );
+        assert!("psc_".parse::<PostcardId>().is_err());
+        assert!("".parse::<CheckId>().is_err());
+    }
+
+    #[test]
+    fn checks_prefixes_when_deserializing() {
+        let id: BankAccountId = serde_json::from_str(r#""bank_8cad8df5354d33f""#).unwrap();
+        assert_eq!(serde_json::to_string(&id).unwrap(), r#""bank_8cad8df5354d33f""#);
+        assert!(serde_json::from_str::<BankAccountId>(r#""chk_8cad8df5354d33f""#).is_err());
+    }
+}
@@ -1,16 +1,34 @@
-pub use self::client::{Client, API_VERSION};
-pub use self::error::Error;
+pub use self::api::LobApi;
+pub use self::client::{Client, ClientBuilder, API_VERSION, DEFAULT_BASE_URL, MAX_BULK_VERIFICATIONS};
+pub use self::error::{Error, ErrorKind, SerdeError};
+pub use self::retry::RetryPolicy;
+pub use self::stream::ListStream;
 
+mod api;
+pub mod builder;
+pub mod cassette;
 mod client;
 mod error;
+#[cfg(test)]
+mod fixtures;
+pub mod fake;
+pub mod id;
+#[cfg(any(test, feature = "mock"))]
+pub mod mock;
 pub mod model;
+mod multipart;
+mod retry;
+mod stream;
+pub mod validate;
+pub mod webhook;
 
 #[cfg(test)]
 mod tests {
-    use crate::{model::*, Client};
+    use crate::{mock::MockLob, model::*, Client};
     use chrono::{Duration, Utc};
     use rand::distributions::{Alphanumeric, DistString};
     use std::collections::BTreeMap;
+    use std::sync::OnceLock;
     use tokio_test::block_on;
 
     #[test]
@@ -49,7 +67,7 @@ mod tests {
             assert_eq!(&address_2, &address);
 
             let delete = client.delete_address(&address.id).await.unwrap();
-            assert_eq!(&delete.id, &address.id);
+            assert_eq!(delete.id, address.id.as_str());
             assert!(&delete.deleted);
 
             let address_3 = client.get_address(&address.id).await.unwrap();
@@ -209,7 +227,7 @@ mod tests {
             assert_eq!(&postcards.data[0], &postcard);
             let delete = client.cancel_postcard(&postcard.id).await.unwrap();
             assert!(delete.deleted);
-            assert_eq!(&delete.id, &postcard.id);
+            assert_eq!(delete.id, postcard.id.as_str());
             let canceled = client.get_postcard(&postcard.id).await.unwrap();
             assert_eq!(&postcard.id, &canceled.id);
             assert_eq!(canceled.deleted, Some(true));
@@ -312,7 +330,7 @@ mod tests {
             assert!(letters.data.iter().any(|l| l == &intl_letter));
             let delete = client.cancel_letter(&us_letter.id).await.unwrap();
             assert!(delete.deleted);
-            assert_eq!(&delete.id, &us_letter.id);
+            assert_eq!(delete.id, us_letter.id.as_str());
             let canceled = client.get_letter(&us_letter.id).await.unwrap();
             assert_eq!(&us_letter.id, &canceled.id);
             assert_eq!(canceled.deleted, Some(true));
@@ -320,7 +338,6 @@ mod tests {
     }
 
     #[test]
-    #[ignore] // requires a real bank account
     fn checks() {
         block_on(async {
             let now = Utc::now().naive_local().date();
@@ -344,6 +361,21 @@ mod tests {
                 })
                 .await
                 .unwrap();
+            let bank_account = client
+                .create_bank_account(&NewBankAccount {
+                    description: None,
+                    routing_number: "021000021".to_string(),
+                    account_number: "12345678901234".to_string(),
+                    account_type: AccountType::Company,
+                    signatory: "me".to_string(),
+                    metadata: rand_key(),
+                })
+                .await
+                .unwrap();
+            client
+                .verify_bank_account(&bank_account.id, [5, 7])
+                .await
+                .unwrap();
             let check = client
                 .create_check(NewCheck {
                     description: Some("another description!".into()),
@@ -357,7 +389,7 @@ mod tests {
                         address_country: None
                     }),
                     from: SendAddress::AddressId(address.id),
-                    bank_account: "a_fake_bank_account".to_string(),
+                    bank_account: bank_account.id,
                     amount: 10.00.into(),
                     memo: None,
                     check_number: None,
@@ -384,7 +416,7 @@ mod tests {
             assert_eq!(&checks.data[0], &check);
             let delete = client.cancel_check(&check.id).await.unwrap();
             assert!(delete.deleted);
-            assert_eq!(&delete.id, &check.id);
+            assert_eq!(delete.id, check.id.as_str());
             let canceled = client.get_check(&check.id).await.unwrap();
             assert_eq!(&check.id, &canceled.id);
             assert_eq!(canceled.deleted, Some(true));
@@ -419,7 +451,7 @@ mod tests {
 
             let delete = client.delete_bank_account(&bank_account.id).await.unwrap();
             assert!(delete.deleted);
-            assert_eq!(&delete.id, &bank_account.id);
+            assert_eq!(delete.id, bank_account.id.as_str());
 
             let deleted = client.get_bank_account(&bank_account.id).await.unwrap();
             assert_eq!(deleted.deleted, Some(true));
@@ -451,8 +483,20 @@ mod tests {
         })
     }
 
+    // Runs against the live API when LOB_API_KEY is set, and against a shared mock otherwise
     fn client() -> Client {
-        Client::new(dotenv::var("LOB_API_KEY").unwrap())
+        static MOCK: OnceLock<MockLob> = OnceLock::new();
+        match dotenv::var("LOB_API_KEY") {
+            Ok(api_key) => Client::new(api_key),
+            Err(_) => MOCK
+                .get_or_init(|| {
+                    // `block_on` cannot be nested in the test's own runtime
+                    std::thread::spawn(|| block_on(MockLob::start()))
+                        .join()
+                        .unwrap()
+                })
+                .client(),
+        }
     }
 
     fn rand_key() -> BTreeMap<String, String> {
@@ -0,0 +1,1115 @@
+//! An in-process stand-in for the Lob API, for testing code that uses `Client` offline and
+//! without an API key.
+//!
+//! `MockLob` serves the address, verification, postcard, letter, check and bank account
+//! endpoints from in-memory state, so created resources can be listed, fetched and canceled
+//! again. Lists are paginated with `after`/`before` cursors like Lob's, and invalid requests are
+//! answered with Lob's error shape and status codes.
+
+use crate::client::{Client, MAX_BULK_VERIFICATIONS};
+use chrono::{Duration, SecondsFormat, Utc};
+use serde_json::{json, Map, Value};
+use std::sync::{Arc, Mutex, PoisonError};
+use wiremock::matchers::any;
+use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
+
+/// The API key of clients returned by `MockLob::client`. Any non-empty key is accepted.
+pub const MOCK_API_KEY: &str = "test_mock_key";
+
+const MAX_LIST_LIMIT: usize = 100;
+const MAX_METADATA_KEYS: usize = 20;
+
+pub struct MockLob {
+    server: MockServer,
+}
+
+impl MockLob {
+    /// Start a server on a random local port. It runs on its own thread, and keeps its state
+    /// until the `MockLob` is dropped.
+    pub async fn start() -> MockLob {
+        let server = MockServer::builder()
+            .disable_request_recording()
+            .start()
+            .await;
+        let handler = Handler {
+            state: Arc::new(Mutex::new(State::default())),
+        };
+        Mock::given(any())
+            .respond_with(handler)
+            .mount(&server)
+            .await;
+        MockLob { server }
+    }
+
+    /// The base URL to pass to `ClientBuilder::base_url`.
+    pub fn uri(&self) -> String {
+        self.server.uri()
+    }
+
+    /// A client sending its requests to this server.
+    pub fn client(&self) -> Client {
+        Client::builder(MOCK_API_KEY)
+            .base_url(self.uri())
+            .build()
+            .expect("mock client options are valid")
+    }
+}
+
+struct Handler {
+    state: Arc<Mutex<State>>,
+}
+
+impl Respond for Handler {
+    fn respond(&self, request: &Request) -> ResponseTemplate {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        match state.handle(request) {
+            Ok(body) => ResponseTemplate::new(200).set_body_json(body),
+            Err(err) => ResponseTemplate::new(err.status).set_body_json(json!({
+                "error": {
+                    "message": err.message,
+                    "status_code": err.status,
+                    "code": err.code,
+                }
+            })),
+        }
+    }
+}
+
+struct ApiError {
+    status: u16,
+    code: &'static str,
+    message: String,
+}
+
+impl ApiError {
+    fn unauthorized() -> ApiError {
+        ApiError {
+            status: 401,
+            code: "unauthorized",
+            message: "Your API key is not valid. Please sign up on lob.com to get a valid api key."
+                .to_owned(),
+        }
+    }
+
+    fn not_found<S: Into<String>>(message: S) -> ApiError {
+        ApiError {
+            status: 404,
+            code: "not_found",
+            message: message.into(),
+        }
+    }
+
+    fn invalid<S: Into<String>>(message: S) -> ApiError {
+        ApiError {
+            status: 422,
+            code: "invalid",
+            message: message.into(),
+        }
+    }
+}
+
+type Response = Result<Value, ApiError>;
+
+#[derive(Default)]
+struct State {
+    next_id: u64,
+    next_check_number: u64,
+    addresses: Collection,
+    postcards: Collection,
+    letters: Collection,
+    checks: Collection,
+    bank_accounts: Collection,
+}
+
+impl State {
+    fn handle(&mut self, request: &Request) -> Response {
+        authorize(request)?;
+        let segments: Vec<&str> = request
+            .url
+            .path_segments()
+            .map(|segments| segments.filter(|s| !s.is_empty()).collect())
+            .unwrap_or_default();
+        let case = query_param(request, "case");
+        match (request.method.as_str(), segments.as_slice()) {
+            ("POST", ["addresses"]) => {
+                let address = new_address(self.id("adr"), &body(request)?)?;
+                Ok(self.addresses.insert(address))
+            }
+            ("GET", ["addresses"]) => self.addresses.list(request),
+            ("GET", ["addresses", id]) => self.addresses.get("address", id),
+            ("DELETE", ["addresses", id]) => self.addresses.delete("address", id),
+
+            ("POST", ["us_verifications"]) => {
+                us_verification(self.id("us_ver"), &body(request)?, case.as_deref())
+            }
+            ("POST", ["bulk", "us_verifications"]) => bulk(&body(request)?, |address| {
+                us_verification(self.id("us_ver"), address, case.as_deref())
+            }),
+            ("POST", ["intl_verifications"]) => {
+                intl_verification(self.id("intl_ver"), &body(request)?)
+            }
+            ("POST", ["bulk", "intl_verifications"]) => bulk(&body(request)?, |address| {
+                intl_verification(self.id("intl_ver"), address)
+            }),
+            ("POST", ["us_autocompletions"]) => {
+                us_autocompletion(self.id("us_auto"), &body(request)?)
+            }
+            ("POST", ["us_zip_lookups"]) => us_zip_lookup(self.id("us_zip"), &body(request)?),
+
+            ("POST", ["postcards"]) => {
+                let postcard = self.new_postcard(&body(request)?)?;
+                Ok(self.postcards.insert(postcard))
+            }
+            ("GET", ["postcards"]) => self.postcards.list(request),
+            ("GET", ["postcards", id]) => self.postcards.get("postcard", id),
+            ("DELETE", ["postcards", id]) => self.postcards.delete("postcard", id),
+
+            ("POST", ["letters"]) => {
+                let letter = self.new_letter(&body(request)?)?;
+                Ok(self.letters.insert(letter))
+            }
+            ("GET", ["letters"]) => self.letters.list(request),
+            ("GET", ["letters", id]) => self.letters.get("letter", id),
+            ("DELETE", ["letters", id]) => self.letters.delete("letter", id),
+
+            ("POST", ["checks"]) => {
+                let check = self.new_check(&body(request)?)?;
+                Ok(self.checks.insert(check))
+            }
+            ("GET", ["checks"]) => self.checks.list(request),
+            ("GET", ["checks", id]) => self.checks.get("check", id),
+            ("DELETE", ["checks", id]) => self.checks.delete("check", id),
+
+            ("POST", ["bank_accounts"]) => {
+                let bank_account = new_bank_account(self.id("bank"), &body(request)?)?;
+                Ok(self.bank_accounts.insert(bank_account))
+            }
+            ("GET", ["bank_accounts"]) => self.bank_accounts.list(request),
+            ("GET", ["bank_accounts", id]) => self.bank_accounts.get("bank account", id),
+            ("DELETE", ["bank_accounts", id]) => self.bank_accounts.delete("bank account", id),
+            ("POST", ["bank_accounts", id, "verify"]) => {
+                let body = body(request)?;
+                self.verify_bank_account(id, &body)
+            }
+
+            (method, _) => Err(ApiError::not_found(format!(
+                "{} {} is not supported by the mock Lob API",
+                method,
+                request.url.path()
+            ))),
+        }
+    }
+
+    fn id(&mut self, prefix: &str) -> String {
+        self.next_id += 1;
+        format!("{}_{:016x}", prefix, self.next_id)
+    }
+
+    /// `to` and `from` are either the ID of a saved address, or an address to create inline.
+    fn address_param(&mut self, body: &Value, field: &str, required: bool) -> Response {
+        match &body[field] {
+            Value::Null if required => Err(ApiError::invalid(format!("{} is required", field))),
+            Value::Null => Ok(Value::Null),
+            Value::String(id) if id.starts_with("adr_") => self.addresses.get("address", id),
+            value @ Value::Object(_) => new_address(self.id("adr"), value),
+            _ => Err(ApiError::invalid(format!(
+                "{} must be an address ID or an address object",
+                field
+            ))),
+        }
+    }
+
+    fn new_postcard(&mut self, body: &Value) -> Response {
+        let to = self.address_param(body, "to", true)?;
+        let from = self.address_param(body, "from", false)?;
+        let front = required(body, "front")?;
+        let back = required(body, "back")?;
+        let size = one_of(body, "size", &["4x6", "6x9", "6x11"], "4x6")?;
+        let id = self.id("psc");
+        let now = Utc::now();
+        Ok(json!({
+            "id": id,
+            "description": body["description"],
+            "metadata": metadata(body)?,
+            "to": to,
+            "from": from,
+            "url": asset_url("postcards", &id),
+            "front_template_id": template_id(front),
+            "back_template_id": template_id(back),
+            "front_template_version_id": null,
+            "back_template_version_id": null,
+            "carrier": "USPS",
+            "tracking_events": [],
+            "thumbnails": thumbnails("postcards", &id, 2),
+            "merge_variables": body["merge_variables"],
+            "size": size,
+            "mail_type": mail_type(body)?,
+            "expected_delivery_date": (now + Duration::days(5)).date_naive().to_string(),
+            "date_created": timestamp(),
+            "date_modified": timestamp(),
+            "send_date": send_date(body),
+            "object": "postcard",
+        }))
+    }
+
+    fn new_letter(&mut self, body: &Value) -> Response {
+        let to = self.address_param(body, "to", true)?;
+        let from = self.address_param(body, "from", true)?;
+        let file = required(body, "file")?;
+        let color =
+            boolean(body, "color")?.ok_or_else(|| ApiError::invalid("color is required"))?;
+        let extra_service = one_of(
+            body,
+            "extra_service",
+            &["certified", "certified_return_receipt", "registered"],
+            "",
+        )?;
+        if !extra_service.is_empty() && to["address_country"] != "UNITED STATES" {
+            return Err(ApiError::invalid(
+                "extra_service is only available for US destinations",
+            ));
+        }
+        let id = self.id("ltr");
+        let now = Utc::now();
+        Ok(json!({
+            "id": id,
+            "description": body["description"],
+            "metadata": metadata(body)?,
+            "to": to,
+            "from": from,
+            "color": color,
+            "double_sided": boolean(body, "double_sided")?.unwrap_or(true),
+            "address_placement": one_of(
+                body,
+                "address_placement",
+                &["top_first_page", "insert_blank_page"],
+                "top_first_page",
+            )?,
+            "return_envelope": boolean(body, "return_envelope")?.unwrap_or(false),
+            "perforated_page": body["perforated_page"],
+            "custom_envelope": null,
+            "extra_service": if extra_service.is_empty() { Value::Null } else { extra_service.into() },
+            "mail_type": mail_type(body)?,
+            "url": asset_url("letters", &id),
+            "merge_variables": body["merge_variables"],
+            "template_id": template_id(file),
+            "template_version_id": null,
+            "carrier": "USPS",
+            "tracking_number": null,
+            "tracking_events": [],
+            "thumbnails": thumbnails("letters", &id, 1),
+            "expected_delivery_date": (now + Duration::days(5)).date_naive().to_string(),
+            "date_created": timestamp(),
+            "date_modified": timestamp(),
+            "send_date": send_date(body),
+            "object": "letter",
+        }))
+    }
+
+    fn new_check(&mut self, body: &Value) -> Response {
+        let to = self.address_param(body, "to", true)?;
+        let from = self.address_param(body, "from", true)?;
+        let bank_account = match &body["bank_account"] {
+            Value::String(id) => self.bank_accounts.get("bank account", id)?,
+            _ => return Err(ApiError::invalid("bank_account is required")),
+        };
+        if bank_account["verified"] != true {
+            return Err(ApiError::invalid("bank account must be verified"));
+        }
+        let amount = match &body["amount"] {
+            Value::Number(n) => n.as_f64(),
+            Value::String(s) => s.parse().ok(),
+            _ => None,
+        }
+        .ok_or_else(|| ApiError::invalid("amount is required"))?;
+        if !(0.0..1_000_000.0).contains(&amount) {
+            return Err(ApiError::invalid("amount must be less than 1000000"));
+        }
+        if body["message"].is_null() == body["check_bottom"].is_null() {
+            return Err(ApiError::invalid(
+                "exactly one of message and check_bottom must be specified",
+            ));
+        }
+        let check_number = match &body["check_number"] {
+            Value::Null => {
+                self.next_check_number += 1;
+                10_000 + self.next_check_number
+            }
+            value => value
+                .as_u64()
+                .or_else(|| value.as_str().and_then(|s| s.parse().ok()))
+                .ok_or_else(|| ApiError::invalid("check_number must be an integer"))?,
+        };
+        let id = self.id("chk");
+        let now = Utc::now();
+        Ok(json!({
+            "id": id,
+            "description": body["description"],
+            "metadata": metadata(body)?,
+            "check_number": check_number,
+            "memo": body["memo"],
+            "amount": amount,
+            "message": body["message"],
+            "url": asset_url("checks", &id),
+            "check_bottom_template_id": template_id(&body["check_bottom"]),
+            "attachment_template_id": template_id(&body["attachment"]),
+            "check_bottom_template_version_id": null,
+            "attachment_template_version_id": null,
+            "to": to,
+            "from": from,
+            "bank_account": bank_account,
+            "carrier": "USPS",
+            "tracking_number": null,
+            "tracking_events": [],
+            "thumbnails": thumbnails("checks", &id, 1),
+            "merge_variables": body["merge_variables"],
+            "expected_delivery_date": (now + Duration::days(5))
+                .to_rfc3339_opts(SecondsFormat::Millis, true),
+            "mail_type": mail_type(body)?,
+            "date_created": timestamp(),
+            "date_modified": timestamp(),
+            "send_date": send_date(body),
+            "object": "check",
+        }))
+    }
+
+    fn verify_bank_account(&mut self, id: &str, body: &Value) -> Response {
+        let amounts: Vec<u64> = body["amounts"]
+            .as_array()
+            .map(|amounts| amounts.iter().filter_map(Value::as_u64).collect())
+            .unwrap_or_default();
+        if amounts.len() != 2 || amounts.iter().any(|amount| !(1..100).contains(amount)) {
+            return Err(ApiError::invalid(
+                "amounts must be two integers between 1 and 99",
+            ));
+        }
+        let bank_account = self.bank_accounts.find_mut("bank account", id)?;
+        bank_account["verified"] = true.into();
+        bank_account["date_modified"] = timestamp().into();
+        Ok(bank_account.clone())
+    }
+}
+
+/// The resources of one type, oldest first.
+#[derive(Default)]
+struct Collection {
+    items: Vec<Value>,
+}
+
+impl Collection {
+    fn insert(&mut self, item: Value) -> Value {
+        self.items.push(item.clone());
+        item
+    }
+
+    fn find_mut(&mut self, name: &str, id: &str) -> Result<&mut Value, ApiError> {
+        self.items
+            .iter_mut()
+            .find(|item| item["id"] == id)
+            .ok_or_else(|| ApiError::not_found(format!("{} not found", name)))
+    }
+
+    fn get(&mut self, name: &str, id: &str) -> Response {
+        self.find_mut(name, id).map(|item| item.clone())
+    }
+
+    fn delete(&mut self, name: &str, id: &str) -> Response {
+        let item = self.find_mut(name, id)?;
+        item["deleted"] = true.into();
+        item["date_modified"] = timestamp().into();
+        Ok(json!({ "id": id, "deleted": true }))
+    }
+
+    /// Newest first, like Lob. The cursors are the IDs of the items a page starts after or ends
+    /// before.
+    fn list(&self, request: &Request) -> Response {
+        let limit = match query_param(request, "limit") {
+            Some(limit) => limit
+                .parse()
+                .ok()
+                .filter(|limit| (1..=MAX_LIST_LIMIT).contains(limit))
+                .ok_or_else(|| {
+                    ApiError::invalid(format!("limit must be between 1 and {}", MAX_LIST_LIMIT))
+                })?,
+            None => 10,
+        };
+        let metadata: Vec<(String, String)> = request
+            .url
+            .query_pairs()
+            .filter_map(|(key, value)| {
+                let key = key.strip_prefix("metadata[")?.strip_suffix(']')?;
+                Some((key.to_owned(), value.into_owned()))
+            })
+            .collect();
+        let items: Vec<&Value> = self
+            .items
+            .iter()
+            .rev()
+            .filter(|item| item["deleted"] != true)
+            .filter(|item| {
+                metadata
+                    .iter()
+                    .all(|(key, value)| item["metadata"][key.as_str()] == value.as_str())
+            })
+            .collect();
+
+        let position = |cursor: &str| {
+            items
+                .iter()
+                .position(|item| item["id"] == cursor)
+                .ok_or_else(|| ApiError::invalid(format!("invalid cursor {}", cursor)))
+        };
+        let (start, end) = match (
+            query_param(request, "after"),
+            query_param(request, "before"),
+        ) {
+            (Some(_), Some(_)) => {
+                return Err(ApiError::invalid(
+                    "after and before cannot both be specified",
+                ))
+            }
+            (Some(after), None) => {
+                let start = position(&after)? + 1;
+                (start, (start + limit).min(items.len()))
+            }
+            (None, Some(before)) => {
+                let end = position(&before)?;
+                (end.saturating_sub(limit), end)
+            }
+            (None, None) => (0, limit.min(items.len())),
+        };
+        let page = &items[start..end];
+
+        let mut list = json!({
+            "object": "list",
+            "data": page,
+            "count": page.len(),
+            "next_url": null,
+            "previous_url": null,
+        });
+        if let (Some(last), true) = (page.last(), end < items.len()) {
+            list["next_url"] = page_url(request, "after", &last["id"]).into();
+        }
+        if let (Some(first), true) = (page.first(), start > 0) {
+            list["previous_url"] = page_url(request, "before", &first["id"]).into();
+        }
+        if request
+            .url
+            .query_pairs()
+            .any(|(key, _)| key.starts_with("include"))
+        {
+            list["total_count"] = items.len().into();
+        }
+        Ok(list)
+    }
+}
+
+fn page_url(request: &Request, param: &str, id: &Value) -> String {
+    let mut url = request.url.clone();
+    let pairs: Vec<(String, String)> = request
+        .url
+        .query_pairs()
+        .filter(|(key, _)| key != "after" && key != "before")
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect();
+    url.query_pairs_mut()
+        .clear()
+        .extend_pairs(pairs)
+        .append_pair(param, id.as_str().unwrap_or_default());
+    url.to_string()
+}
+
+fn new_address(id: String, body: &Value) -> Response {
+    required(body, "address_line1")?;
+    let country = match body["address_country"].as_str() {
+        None | Some("US") => "UNITED STATES".to_owned(),
+        Some(country) => country.to_uppercase(),
+    };
+    Ok(json!({
+        "id": id,
+        "description": body["description"],
+        "name": body["name"],
+        "company": body["company"],
+        "phone": body["phone"],
+        "email": body["email"],
+        "address_line1": body["address_line1"],
+        "address_line2": body["address_line2"],
+        "address_city": body["address_city"],
+        "address_state": body["address_state"],
+        "address_zip": body["address_zip"],
+        "address_country": country,
+        "metadata": metadata(body)?,
+        "date_created": timestamp(),
+        "date_modified": timestamp(),
+        "object": "address",
+    }))
+}
+
+fn new_bank_account(id: String, body: &Value) -> Response {
+    let routing_number = required(body, "routing_number")?
+        .as_str()
+        .filter(|n| n.len() == 9 && n.bytes().all(|b| b.is_ascii_digit()))
+        .ok_or_else(|| ApiError::invalid("routing_number must be 9 digits"))?;
+    required(body, "account_number")?;
+    required(body, "signatory")?;
+    let account_type = one_of(body, "account_type", &["company", "individual"], "")?;
+    if account_type.is_empty() {
+        return Err(ApiError::invalid("account_type is required"));
+    }
+    let bank_name = match routing_number {
+        "021000021" => "JPMORGAN CHASE BANK, NA",
+        _ => "MOCK BANK",
+    };
+    Ok(json!({
+        "id": id,
+        "description": body["description"],
+        "metadata": metadata(body)?,
+        "routing_number": routing_number,
+        "account_number": body["account_number"],
+        "account_type": account_type,
+        "signatory": body["signatory"],
+        "signature_url": null,
+        "bank_name": bank_name,
+        "verified": false,
+        "date_created": timestamp(),
+        "date_modified": timestamp(),
+        "object": "bank_account",
+    }))
+}
+
+/// Lob's test environment picks the result by keywords in the primary line, e.g.
+/// `undeliverable` or `missing unit`.
+fn us_verification(id: String, body: &Value, case: Option<&str>) -> Response {
+    let (primary_line, city, state, zip_code) = match body["address"].as_str() {
+        Some(address) => {
+            let mut parts = address.split(',').map(str::trim);
+            let primary_line = parts.next().unwrap_or_default().to_owned();
+            (
+                primary_line,
+                parts.next().unwrap_or_default().to_owned(),
+                String::new(),
+                String::new(),
+            )
+        }
+        None => (
+            body["primary_line"].as_str().unwrap_or_default().to_owned(),
+            body["city"].as_str().unwrap_or_default().to_owned(),
+            body["state"].as_str().unwrap_or_default().to_owned(),
+            body["zip_code"].as_str().unwrap_or_default().to_owned(),
+        ),
+    };
+    if primary_line.is_empty() {
+        return Err(ApiError::invalid("primary_line is required"));
+    }
+    let keywords = primary_line.to_lowercase();
+    let (deliverability, dpv_confirmation) = if keywords.contains("undeliverable") {
+        ("undeliverable", "N")
+    } else if keywords.contains("missing unit") {
+        ("deliverable_missing_unit", "D")
+    } else {
+        ("deliverable", "Y")
+    };
+    let record_type = if keywords.contains("highrise") {
+        "highrise"
+    } else {
+        "street"
+    };
+    let set_case = |s: &str| match case {
+        Some("lower") => s.to_lowercase(),
+        _ => s.to_uppercase(),
+    };
+    let city = if city.is_empty() {
+        "BOULDER".to_owned()
+    } else {
+        city
+    };
+    let state = if state.is_empty() {
+        "CO".to_owned()
+    } else {
+        state
+    };
+    let zip_code = if zip_code.is_empty() {
+        "80303".to_owned()
+    } else {
+        zip_code
+    };
+    Ok(json!({
+        "id": id,
+        "recipient": set_case(body["recipient"].as_str().unwrap_or_default()),
+        "primary_line": set_case(&primary_line),
+        "secondary_line": set_case(body["secondary_line"].as_str().unwrap_or_default()),
+        "urbanization": "",
+        "last_line": set_case(&format!("{} {} {}-1234", city, state, zip_code)),
+        "deliverability": deliverability,
+        "components": {
+            "primary_number": "1",
+            "street_predirection": "",
+            "street_name": set_case(&primary_line),
+            "street_suffix": "",
+            "street_postdirection": "",
+            "secondary_designator": "",
+            "secondary_number": "",
+            "pmb_designator": "",
+            "pmb_number": "",
+            "extra_secondary_designator": "",
+            "extra_secondary_number": "",
+            "city": set_case(&city),
+            "state": set_case(&state),
+            "zip_code": zip_code,
+            "zip_code_plus_4": "1234",
+            "zip_code_type": "standard",
+            "delivery_point_barcode": format!("{}12341", zip_code),
+            "address_type": "residential",
+            "record_type": record_type,
+            "default_building_address": false,
+            "county": set_case(&city),
+            "county_fips": "08013",
+            "carrier_route": "C001",
+            "carrier_route_type": "city_delivery",
+            "latitude": 40.0,
+            "longitude": -105.27,
+        },
+        "deliverability_analysis": {
+            "dpv_confirmation": dpv_confirmation,
+            "dpv_cmra": "N",
+            "dpv_vacant": "N",
+            "dpv_active": "Y",
+            "dpv_footnotes": ["AA", "BB"],
+            "ews_match": false,
+            "lacs_indicator": "",
+            "lacs_return_code": "",
+            "suite_return_code": "",
+        },
+        "object": "us_verification",
+    }))
+}
+
+fn intl_verification(id: String, body: &Value) -> Response {
+    let primary_line = required(body, "primary_line")?.as_str().unwrap_or_default();
+    let country = required(body, "country")?
+        .as_str()
+        .filter(|country| country.len() == 2)
+        .ok_or_else(|| ApiError::invalid("country must be a 2 letter country code"))?
+        .to_uppercase();
+    if ["US", "AS", "PR", "FM", "GU", "MH", "MP", "PW", "VI"].contains(&country.as_str()) {
+        return Err(ApiError::invalid(
+            "country must not be a US territory, use US verifications instead",
+        ));
+    }
+    let text = |key: &str| body[key].as_str().unwrap_or_default().to_uppercase();
+    Ok(json!({
+        "id": id,
+        "recipient": text("recipient"),
+        "primary_line": primary_line.to_uppercase(),
+        "secondary_line": text("secondary_line"),
+        "last_line": format!("{} {} {}", text("city"), text("state"), text("postal_code"))
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" "),
+        "country": country,
+        "deliverability": "deliverable",
+        "components": {
+            "primary_object": null,
+            "street_name": primary_line.to_uppercase(),
+            "city": text("city"),
+            "state": text("state"),
+            "postal_code": text("postal_code"),
+        },
+        "object": "intl_verification",
+    }))
+}
+
+fn bulk<F>(body: &Value, mut verify: F) -> Response
+where
+    F: FnMut(&Value) -> Response,
+{
+    let addresses = body["addresses"]
+        .as_array()
+        .filter(|addresses| (1..=MAX_BULK_VERIFICATIONS).contains(&addresses.len()))
+        .ok_or_else(|| {
+            ApiError::invalid(format!(
+                "addresses must contain between 1 and {} addresses",
+                MAX_BULK_VERIFICATIONS
+            ))
+        })?;
+    let mut errors = false;
+    let addresses: Vec<Value> = addresses
+        .iter()
+        .map(|address| {
+            verify(address).unwrap_or_else(|err| {
+                errors = true;
+                json!({ "error": { "message": err.message, "status_code": err.status } })
+            })
+        })
+        .collect();
+    Ok(json!({ "addresses": addresses, "errors": errors }))
+}
+
+fn us_autocompletion(id: String, body: &Value) -> Response {
+    let prefix = required(body, "address_prefix")?
+        .as_str()
+        .unwrap_or_default()
+        .to_uppercase();
+    let city = body["city"].as_str().unwrap_or("BOULDER").to_uppercase();
+    let state = body["state"].as_str().unwrap_or("CO").to_uppercase();
+    let suggestions: Vec<Value> = ["MAIN ST", "SPRUCE ST", "SUNSET BLVD"]
+        .iter()
+        .map(|street| {
+            json!({
+                "primary_line": format!("{} {}", prefix, street),
+                "city": city,
+                "state": state,
+                "zip_code": "80303",
+            })
+        })
+        .collect();
+    Ok(json!({
+        "id": id,
+        "suggestions": suggestions,
+        "object": "us_autocompletion",
+    }))
+}
+
+fn us_zip_lookup(id: String, body: &Value) -> Response {
+    let zip_code = required(body, "zip_code")?
+        .as_str()
+        .filter(|zip| zip.len() == 5 && zip.bytes().all(|b| b.is_ascii_digit()))
+        .ok_or_else(|| ApiError::invalid("zip_code must be in a valid zip or zip+4 format"))?;
+    Ok(json!({
+        "id": id,
+        "zip_code": zip_code,
+        "zip_code_type": "standard",
+        "cities": [{
+            "city": "BOULDER",
+            "state": "CO",
+            "county": "BOULDER",
+            "county_fips": "08013",
+            "preferred": true,
+        }],
+        "object": "us_zip_lookup",
+    }))
+}
+
+fn authorize(request: &Request) -> Result<(), ApiError> {
+    // An empty API key and password encode to `Og==`
+    match request.headers.get("authorization").map(|v| v.to_str()) {
+        Some(Ok(auth)) if auth.starts_with("Basic ") && auth != "Basic Og==" => Ok(()),
+        _ => Err(ApiError::unauthorized()),
+    }
+}
+
+fn query_param(request: &Request, name: &str) -> Option<String> {
+    request
+        .url
+        .query_pairs()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.into_owned())
+}
+
+/// The request body as JSON, whether it was sent as JSON or as a multipart form.
+fn body(request: &Request) -> Response {
+    let content_type = request
+        .headers
+        .get("content-type")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+    let body = if content_type.starts_with("multipart/form-data") {
+        multipart_fields(content_type, &request.body)?
+    } else if request.body.is_empty() {
+        json!({})
+    } else {
+        serde_json::from_slice(&request.body)
+            .map_err(|err| ApiError::invalid(format!("invalid JSON body: {}", err)))?
+    };
+    match body {
+        Value::Object(_) => Ok(body),
+        _ => Err(ApiError::invalid("request body must be an object")),
+    }
+}
+
+/// Text fields named like `to[address_line1]` are nested back into objects, and uploaded files
+/// are replaced by the URL Lob would host them at.
+fn multipart_fields(content_type: &str, body: &[u8]) -> Response {
+    let boundary = content_type
+        .split(';')
+        .find_map(|param| param.trim().strip_prefix("boundary="))
+        .map(|boundary| boundary.trim_matches('"'))
+        .ok_or_else(|| ApiError::invalid("multipart body without a boundary"))?;
+    let body = String::from_utf8_lossy(body);
+    let mut fields = Value::Object(Map::new());
+    for part in body.split(&format!("--{}", boundary)).skip(1) {
+        if part.starts_with("--") {
+            break;
+        }
+        let (head, content) = part
+            .split_once("\r\n\r\n")
+            .ok_or_else(|| ApiError::invalid("malformed multipart body"))?;
+        let disposition = |param: &str| {
+            let start = head.find(&format!("{}=\"", param))? + param.len() + 2;
+            let len = head[start..].find('"')?;
+            Some(head[start..start + len].to_owned())
+        };
+        let name = disposition("name")
+            .ok_or_else(|| ApiError::invalid("multipart part without a name"))?;
+        let value = match disposition("filename") {
+            Some(filename) => format!("https://lob-assets.com/uploads/{}", filename),
+            None => content.strip_suffix("\r\n").unwrap_or(content).to_owned(),
+        };
+        let mut field = &mut fields;
+        for key in name.split('[').map(|key| key.trim_end_matches(']')) {
+            if !field.is_object() {
+                *field = Value::Object(Map::new());
+            }
+            field = field
+                .as_object_mut()
+                .expect("just made an object")
+                .entry(key)
+                .or_insert(Value::Null);
+        }
+        *field = value.into();
+    }
+    Ok(fields)
+}
+
+fn required<'a>(body: &'a Value, field: &str) -> Result<&'a Value, ApiError> {
+    match &body[field] {
+        Value::Null => Err(ApiError::invalid(format!("{} is required", field))),
+        Value::String(s) if s.is_empty() => Err(ApiError::invalid(format!(
+            "{} is not allowed to be empty",
+            field
+        ))),
+        value => Ok(value),
+    }
+}
+
+/// `field` as one of `allowed`, or `default` when it was not sent.
+fn one_of(body: &Value, field: &str, allowed: &[&str], default: &str) -> Result<String, ApiError> {
+    match &body[field] {
+        Value::Null => Ok(default.to_owned()),
+        Value::String(s) if allowed.contains(&s.as_str()) => Ok(s.clone()),
+        _ => Err(ApiError::invalid(format!(
+            "{} must be one of {}",
+            field,
+            allowed.join(", ")
+        ))),
+    }
+}
+
+/// Booleans arrive as strings from multipart forms.
+fn boolean(body: &Value, field: &str) -> Result<Option<bool>, ApiError> {
+    match &body[field] {
+        Value::Null => Ok(None),
+        Value::Bool(b) => Ok(Some(*b)),
+        Value::String(s) if s == "true" || s == "false" => Ok(Some(s == "true")),
+        _ => Err(ApiError::invalid(format!("{} must be a boolean", field))),
+    }
+}
+
+fn metadata(body: &Value) -> Response {
+    match &body["metadata"] {
+        Value::Null => Ok(json!({})),
+        Value::Object(map) if map.len() > MAX_METADATA_KEYS => Err(ApiError::invalid(format!(
+            "metadata must have at most {} keys",
+            MAX_METADATA_KEYS
+        ))),
+        Value::Object(map) if map.values().all(Value::is_string) => Ok(body["metadata"].clone()),
+        _ => Err(ApiError::invalid("metadata values must be strings")),
+    }
+}
+
+fn mail_type(body: &Value) -> Result<String, ApiError> {
+    one_of(
+        body,
+        "mail_type",
+        &["usps_first_class", "usps_standard", "ups_next_day_air"],
+        "usps_first_class",
+    )
+}
+
+fn template_id(input: &Value) -> Value {
+    match input.as_str() {
+        Some(id) if id.starts_with("tmpl_") => id.into(),
+        _ => Value::Null,
+    }
+}
+
+/// Checks are scheduled by date, which Lob answers with midnight of that date.
+fn send_date(body: &Value) -> Value {
+    match &body["send_date"] {
+        Value::Null => timestamp().into(),
+        Value::String(date) if date.len() == "2020-02-11".len() => {
+            format!("{}T00:00:00.000Z", date).into()
+        }
+        send_date => send_date.clone(),
+    }
+}
+
+fn asset_url(resource: &str, id: &str) -> String {
+    format!("https://lob-assets.com/{}/{}.pdf", resource, id)
+}
+
+fn thumbnails(resource: &str, id: &str, pages: usize) -> Value {
+    (1..=pages)
+        .map(|page| {
+            let url = |size: &str| {
+                format!(
+                    "https://lob-assets.com/{}/{}_thumb_{}_{}.png",
+                    resource, id, size, page
+                )
+            };
+            json!({ "large": url("large"), "medium": url("medium"), "small": url("small") })
+        })
+        .collect()
+}
+
+fn timestamp() -> String {
+    Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::id::BankAccountId;
+    use crate::model::*;
+    use futures::TryStreamExt;
+    use std::collections::BTreeMap;
+    use tokio_test::block_on;
+
+    fn new_address(key: &str) -> NewAddress {
+        let mut metadata = BTreeMap::new();
+        metadata.insert("batch".to_owned(), key.to_owned());
+        NewAddress {
+            description: None,
+            name: Some("Wyyerd Central".to_owned()),
+            company: None,
+            phone: None,
+            email: None,
+            address_line1: "5600 Arapahoe Ave. STE 200".to_owned(),
+            address_line2: None,
+            address_city: Some("Boulder".to_owned()),
+            address_state: Some("CO".to_owned()),
+            address_zip: Some("80304".to_owned()),
+            address_country: Some("US".to_owned()),
+            metadata: Some(metadata),
+        }
+    }
+
+    #[test]
+    fn paginates_with_cursors() {
+        block_on(async {
+            let lob = MockLob::start().await;
+            let client = lob.client();
+            for _ in 0..5 {
+                client.create_address(new_address("a")).await.unwrap();
+            }
+            client.create_address(new_address("b")).await.unwrap();
+
+            let mut metadata = BTreeMap::new();
+            metadata.insert("batch".to_owned(), "a".to_owned());
+            let options = ListAddressesOptions {
+                limit: Some(2),
+                metadata: Some(metadata),
+                ..ListAddressesOptions::default()
+            };
+            let first = client.list_addresses(Some(options.clone())).await.unwrap();
+            assert_eq!(first.count, 2);
+            assert!(first.previous_url.is_none());
+            let second = client
+                .list_addresses(options.next_page_options(&first))
+                .await
+                .unwrap();
+            assert_ne!(first.data[0].id, second.data[0].id);
+            assert!(second.previous_url.is_some());
+
+            let all: Vec<_> = client
+                .list_addresses_stream(Some(options))
+                .try_collect()
+                .await
+                .unwrap();
+            assert_eq!(all.len(), 5);
+        })
+    }
+
+    #[test]
+    fn returns_lob_errors() {
+        block_on(async {
+            let lob = MockLob::start().await;
+            let err = lob
+                .client()
+                .get_postcard(&"psc_0000000000000000".parse().unwrap())
+                .await
+                .unwrap_err();
+            assert!(err.is_not_found());
+            assert_eq!(err.lob_code(), Some("not_found"));
+
+            let err = lob
+                .client()
+                .list_addresses(Some(ListAddressesOptions {
+                    after: Some("adr_unknown".to_owned()),
+                    ..ListAddressesOptions::default()
+                }))
+                .await
+                .unwrap_err();
+            assert!(err.is_validation());
+
+            let err = Client::builder("")
+                .base_url(lob.uri())
+                .build()
+                .unwrap()
+                .us_zip_lookup("80303")
+                .await
+                .unwrap_err();
+            assert!(err.is_auth());
+        })
+    }
+
+    #[test]
+    fn requires_verified_bank_accounts_for_checks() {
+        block_on(async {
+            let lob = MockLob::start().await;
+            let client = lob.client();
+            let address = client.create_address(new_address("c")).await.unwrap();
+            let bank_account = client
+                .create_bank_account(&NewBankAccount {
+                    description: None,
+                    routing_number: "021000021".to_owned(),
+                    account_number: "12345678901234".to_owned(),
+                    account_type: AccountType::Company,
+                    signatory: "me".to_owned(),
+                    metadata: BTreeMap::new(),
+                })
+                .await
+                .unwrap();
+            let check = |bank_account: BankAccountId| NewCheck {
+                description: None,
+                to: SendAddress::AddressId(address.id.clone()),
+                from: SendAddress::AddressId(address.id.clone()),
+                bank_account,
+                amount: CheckAmount::new(10, 0),
+                memo: None,
+                check_number: None,
+                logo: None,
+                message: Some("Thanks!".to_owned()),
+                check_bottom: None,
+                attachment: None,
+                mail_type: None,
+                use_type: None,
+                send_date: None,
+                metadata: None,
+            };
+            let err = client
+                .create_check(check(bank_account.id.clone()))
+                .await
+                .unwrap_err();
+            assert_eq!(err.status(), Some(422));
+
+            client
+                .verify_bank_account(&bank_account.id, [5, 7])
+                .await
+                .unwrap();
+            let created = client.create_check(check(bank_account.id)).await.unwrap();
+            assert_eq!(created.amount, CheckAmount::new(10, 0));
+            assert!(created.bank_account.verified);
+        })
+    }
+}
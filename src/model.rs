@@ -1,3 +1,4 @@
+use crate::id::*;
 use chrono::{DateTime, NaiveDate, Utc};
 use std::fmt::Formatter;
 use serde::{ser::Error, Deserialize, Deserializer, Serialize, Serializer};
@@ -6,9 +7,72 @@ use std::fmt;
 use std::net::IpAddr;
 use std::str::FromStr;
 
+/// An enum of the string values Lob documents for a field, with an `Unknown` fallback so values
+/// added to the API later still deserialize, and serialize back unchanged. Since `Unknown` holds
+/// a `String`, the enums are `Clone` but not `Copy`.
+macro_rules! string_enum {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $(
+                $(#[$variant_meta:meta])*
+                $variant:ident = $value:literal,
+            )*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $(
+                $(#[$variant_meta])*
+                $variant,
+            )*
+            /// A value not known to this version of the crate.
+            Unknown(String),
+        }
+
+        impl $name {
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $($name::$variant => $value,)*
+                    $name::Unknown(value) => value,
+                }
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(value: &str) -> Self {
+                match value {
+                    $($value => $name::$variant,)*
+                    other => $name::Unknown(other.to_owned()),
+                }
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let value: std::borrow::Cow<'de, str> = Deserialize::deserialize(deserializer)?;
+                Ok($name::from(value.as_ref()))
+            }
+        }
+    };
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Address {
-    pub id: String,
+    pub id: AddressId,
     pub description: Option<String>,
     pub name: Option<String>,
     pub company: Option<String>,
@@ -88,10 +152,30 @@ pub enum AddressVerificationInput {
     Components(AddressVerificationComponents),
 }
 
+impl Serialize for AddressVerificationInput {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+        match self {
+            AddressVerificationInput::Flat(address) => {
+                let mut map = serializer.serialize_map(Some(1))?;
+                map.serialize_entry("address", address)?;
+                map.end()
+            }
+            AddressVerificationInput::Components(components) => components.serialize(serializer),
+        }
+    }
+}
+
 pub trait VerifyAddress {
     fn into_input(self) -> AddressVerificationInput;
 }
 
+impl VerifyAddress for AddressVerificationInput {
+    fn into_input(self) -> AddressVerificationInput {
+        self
+    }
+}
+
 impl VerifyAddress for AddressVerificationComponents {
     fn into_input(self) -> AddressVerificationInput {
         AddressVerificationInput::Components(self)
@@ -110,14 +194,14 @@ impl VerifyAddress for &str {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
-pub enum Deliverability {
-    Deliverable,
-    DeliverableUnnecessaryUnit,
-    DeliverableIncorrectUnit,
-    DeliverableMissingUnit,
-    Undeliverable,
+string_enum! {
+    pub enum Deliverability {
+        Deliverable = "deliverable",
+        DeliverableUnnecessaryUnit = "deliverable_unnecessary_unit",
+        DeliverableIncorrectUnit = "deliverable_incorrect_unit",
+        DeliverableMissingUnit = "deliverable_missing_unit",
+        Undeliverable = "undeliverable",
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -187,115 +271,115 @@ pub struct DeliverabilityAnalysis {
     //    object: object::UsVerification,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
-pub enum ZipCodeType {
-    Standard,
-    Military,
-    Unique,
-    PoBox,
+string_enum! {
+    pub enum ZipCodeType {
+        Standard = "standard",
+        Military = "military",
+        Unique = "unique",
+        PoBox = "po_box",
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
-pub enum AddressType {
-    Residential,
-    Commercial,
+string_enum! {
+    pub enum AddressType {
+        Residential = "residential",
+        Commercial = "commercial",
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
-pub enum RecordType {
-    Street,
-    Highrise,
-    Firm,
-    PoBox,
-    RuralRoute,
-    GeneralDelivery,
+string_enum! {
+    pub enum RecordType {
+        Street = "street",
+        Highrise = "highrise",
+        Firm = "firm",
+        PoBox = "po_box",
+        RuralRoute = "rural_route",
+        GeneralDelivery = "general_delivery",
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
-pub enum CarrierRouteType {
-    CityDelivery,
-    RuralRoute,
-    HighwayContract,
-    PoBox,
-    GeneralDelivery,
-    Contract, // `Contract` doesn't appear in their documentation, but has been returned
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-pub enum DpvConfirmation {
-    /// The address is deliverable by the USPS.
-    Y,
-    /// The address is deliverable by removing the provided secondary unit designator. This
-    /// information may be incorrect or unnecessary.
-    S,
-    /// The address is deliverable to the building's default address but is missing a secondary unit
-    /// designator and/or number. There is a chance the mail will not reach the intended recipient.
-    D,
-    /// The address is not deliverable according to the USPS, but parts of the address are valid
-    /// (such as the street and ZIP code).
-    N,
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-pub enum DpvCode {
-    /// Some parts of the address (such as the street and ZIP code) are valid.
-    AA,
-    /// The address is invalid based on given inputs.
-    A1,
-    /// The address is deliverable.
-    BB,
-    /// Address is a deliverable address after dropping the sub-building number from the input address. Sub-building is required.
-    C1,
-    /// The address is deliverable by removing the provided secondary unit designator.
-    CC,
-    /// The address is deliverable but is missing a secondary information (apartment, unit, etc).
-    N1,
-    /// The address is a deliverable military address.
-    F1,
-    /// The address is a deliverable General Delivery address. General Delivery is a USPS service which allows individuals without permanent addresses to receive mail.
-    G1,
-    /// The address is a deliverable unique address. A unique ZIP code is assigned to a single organization (such as a government agency) that receives a large volume of mail.
-    U1,
-    /// The primary number is missing.
-    M1,
-    /// The primary number is invalid.
-    M3,
-    /// PO Box, Rural Route, or Highway Contract box number is missing.
-    P1,
-    /// PO Box, Rural Route, or Highway Contract box number is invalid.
-    P3,
-    /// The address matched to a CMRA and private mailbox information is not present.
-    R1,
-    /// The address matched to a Phantom Carrier Route (carrier_route of R777), which corresponds to physical addresses that are not eligible for delivery.
-    R7,
-    /// The address matched to a CMRA and private mailbox information is present.
-    RR,
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-pub enum LacsReturnCode {
-    /// A new address was produced because a match was found in LACSLink.
-    A,
-    #[serde(rename = "92")]
-    /// A LACSLink record was matched after dropping secondary information.
-    _92,
-    #[serde(rename = "14")]
-    /// A match was found in LACSLink, but could not be converted to a deliverable address.
-    _14,
-    #[serde(rename = "00")]
-    /// A match was not found in LACSLink, and no new address was produced.
-    _00,
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-pub enum SuiteReturnCode {
-    A, // A SuiteLink match was found and secondary information was added.
-    #[serde(rename = "00")]
-    _00, // A SuiteLink match could not be found and no secondary information was added.
+string_enum! {
+    pub enum CarrierRouteType {
+        CityDelivery = "city_delivery",
+        RuralRoute = "rural_route",
+        HighwayContract = "highway_contract",
+        PoBox = "po_box",
+        GeneralDelivery = "general_delivery",
+        Contract = "contract", // `Contract` doesn't appear in their documentation, but has been returned
+    }
+}
+
+string_enum! {
+    pub enum DpvConfirmation {
+        /// The address is deliverable by the USPS.
+        Y = "Y",
+        /// The address is deliverable by removing the provided secondary unit designator. This
+        /// information may be incorrect or unnecessary.
+        S = "S",
+        /// The address is deliverable to the building's default address but is missing a secondary unit
+        /// designator and/or number. There is a chance the mail will not reach the intended recipient.
+        D = "D",
+        /// The address is not deliverable according to the USPS, but parts of the address are valid
+        /// (such as the street and ZIP code).
+        N = "N",
+    }
+}
+
+string_enum! {
+    pub enum DpvCode {
+        /// Some parts of the address (such as the street and ZIP code) are valid.
+        AA = "AA",
+        /// The address is invalid based on given inputs.
+        A1 = "A1",
+        /// The address is deliverable.
+        BB = "BB",
+        /// Address is a deliverable address after dropping the sub-building number from the input address. Sub-building is required.
+        C1 = "C1",
+        /// The address is deliverable by removing the provided secondary unit designator.
+        CC = "CC",
+        /// The address is deliverable but is missing a secondary information (apartment, unit, etc).
+        N1 = "N1",
+        /// The address is a deliverable military address.
+        F1 = "F1",
+        /// The address is a deliverable General Delivery address. General Delivery is a USPS service which allows individuals without permanent addresses to receive mail.
+        G1 = "G1",
+        /// The address is a deliverable unique address. A unique ZIP code is assigned to a single organization (such as a government agency) that receives a large volume of mail.
+        U1 = "U1",
+        /// The primary number is missing.
+        M1 = "M1",
+        /// The primary number is invalid.
+        M3 = "M3",
+        /// PO Box, Rural Route, or Highway Contract box number is missing.
+        P1 = "P1",
+        /// PO Box, Rural Route, or Highway Contract box number is invalid.
+        P3 = "P3",
+        /// The address matched to a CMRA and private mailbox information is not present.
+        R1 = "R1",
+        /// The address matched to a Phantom Carrier Route (carrier_route of R777), which corresponds to physical addresses that are not eligible for delivery.
+        R7 = "R7",
+        /// The address matched to a CMRA and private mailbox information is present.
+        RR = "RR",
+    }
+}
+
+string_enum! {
+    pub enum LacsReturnCode {
+        /// A new address was produced because a match was found in LACSLink.
+        A = "A",
+        /// A LACSLink record was matched after dropping secondary information.
+        _92 = "92",
+        /// A match was found in LACSLink, but could not be converted to a deliverable address.
+        _14 = "14",
+        /// A match was not found in LACSLink, and no new address was produced.
+        _00 = "00",
+    }
+}
+
+string_enum! {
+    pub enum SuiteReturnCode {
+        A = "A", // A SuiteLink match was found and secondary information was added.
+        _00 = "00", // A SuiteLink match could not be found and no secondary information was added.
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -305,7 +389,7 @@ pub struct UsAutocompletion {
     object: object::UsAutocompletion,
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct AutocompleteAddressOptions {
     pub city: Option<String>,
     pub state: Option<String>,
@@ -397,6 +481,33 @@ pub struct InternationalVerificationInput {
     pub country: String,
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct BulkVerificationBody<'a, T> {
+    pub addresses: &'a [T],
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct BulkVerificationResponse<T> {
+    pub addresses: Vec<BulkVerificationEntry<T>>,
+}
+
+/// One address of a bulk verification, which Lob either verified or rejected on its own.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum BulkVerificationEntry<T> {
+    Failed { error: LobError },
+    Verified(T),
+}
+
+impl<T> BulkVerificationEntry<T> {
+    pub fn into_result(self) -> Result<T, LobError> {
+        match self {
+            BulkVerificationEntry::Failed { error } => Err(error),
+            BulkVerificationEntry::Verified(verification) => Ok(verification),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InternationalAddressComponents {
     pub primary_object: Option<String>,
@@ -408,16 +519,16 @@ pub struct InternationalAddressComponents {
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Postcard {
-    pub id: String,
+    pub id: PostcardId,
     pub description: Option<String>,
     pub metadata: BTreeMap<String, String>,
     pub to: Address,
     pub from: Option<Address>,
     pub url: String,
-    pub front_template_id: Option<String>,
-    pub back_template_id: Option<String>,
-    pub front_template_version_id: Option<String>,
-    pub back_template_version_id: Option<String>,
+    pub front_template_id: Option<TemplateId>,
+    pub back_template_id: Option<TemplateId>,
+    pub front_template_version_id: Option<TemplateVersionId>,
+    pub back_template_version_id: Option<TemplateVersionId>,
     pub carrier: String,
     pub tracking_events: Vec<TrackingEvent>,
     pub thumbnails: Vec<Thumbnails>,
@@ -498,25 +609,19 @@ pub enum Order {
 #[derive(Debug, Clone, Serialize)]
 #[serde(untagged)]
 pub enum SendAddress {
-    AddressId(String),
+    AddressId(AddressId),
     Components(SendAddressComponents),
 }
 
-impl Into<SendAddress> for String {
-    fn into(self) -> SendAddress {
-        SendAddress::AddressId(self)
+impl From<AddressId> for SendAddress {
+    fn from(id: AddressId) -> SendAddress {
+        SendAddress::AddressId(id)
     }
 }
 
-impl Into<SendAddress> for &str {
-    fn into(self) -> SendAddress {
-        SendAddress::AddressId(self.to_owned())
-    }
-}
-
-impl Into<SendAddress> for SendAddressComponents {
-    fn into(self) -> SendAddress {
-        SendAddress::Components(self)
+impl From<SendAddressComponents> for SendAddress {
+    fn from(components: SendAddressComponents) -> SendAddress {
+        SendAddress::Components(components)
     }
 }
 
@@ -531,9 +636,80 @@ pub struct SendAddressComponents {
     pub address_country: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SelfMailer {
+    pub id: SelfMailerId,
+    pub description: Option<String>,
+    pub metadata: BTreeMap<String, String>,
+    pub to: Address,
+    pub from: Option<Address>,
+    pub url: String,
+    pub inside_template_id: Option<TemplateId>,
+    pub outside_template_id: Option<TemplateId>,
+    pub inside_template_version_id: Option<TemplateVersionId>,
+    pub outside_template_version_id: Option<TemplateVersionId>,
+    #[serde(default)]
+    pub tracking_events: Vec<TrackingEvent>,
+    pub merge_variables: Option<BTreeMap<String, String>>,
+    pub size: SelfMailerSize,
+    pub mail_type: MailType,
+    pub use_type: Option<UseType>,
+    pub expected_delivery_date: Option<NaiveDate>,
+    pub date_created: DateTime<Utc>,
+    pub date_modified: DateTime<Utc>,
+    pub send_date: DateTime<Utc>,
+    pub deleted: Option<bool>,
+    object: object::SelfMailer,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NewSelfMailer {
+    pub description: Option<String>,
+    pub to: SendAddress,
+    pub from: Option<SendAddress>,
+    /// The artwork for the inside of the self-mailer: an HTML string, the ID of a saved template,
+    /// or a remote URL or local upload of an HTML, PDF, PNG or JPG file.
+    #[serde(skip_serializing_if = "FileInput::is_file")]
+    pub inside: FileInput,
+    /// The artwork for the outside of the self-mailer, accepting the same inputs as `inside`.
+    #[serde(skip_serializing_if = "FileInput::is_file")]
+    pub outside: FileInput,
+    pub merge_variables: Option<BTreeMap<String, String>>,
+    pub size: Option<SelfMailerSize>,
+    pub mail_type: Option<MailType>,
+    pub use_type: Option<UseType>,
+    pub send_date: Option<DateTime<Utc>>,
+    pub metadata: Option<BTreeMap<String, String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct ListSelfMailerOptions {
+    /// An integer that designates how many results to return. Defaults to 10 and must be no more than 100.
+    pub limit: Option<i32>,
+    /// A reference to a list entry used for paginating to the previous set of entries. This field is pre-populated in the previous_url field in the return response.
+    pub after: Option<String>,
+    /// A reference to a list entry used for paginating to the next set of entries. This field is pre-populated in the next_url field in the return response.
+    pub before: Option<String>,
+    /// Request that the response include the total count by specifying include[]=total_count.
+    pub include: Option<Vec<ListIncludeOptions>>,
+    /// Filter by metadata key-value pair, e.g. metadata[customer_id]=987654.
+    pub metadata: Option<BTreeMap<String, String>>,
+    /// Filter by ISO-8601 date or datetime, e.g. { gt: '2012-01-01', lt: '2012-01-31T12:34:56Z' } where gt is ›, lt is ‹, gte is ≥, and lte is ≤.
+    pub date_created: Option<DateFilter>,
+    /// The self-mailer size to be returned.
+    pub size: Option<SelfMailerSize>,
+    /// Set scheduled to true to only return orders (past or future) where send_date is greater than date_created. Set scheduled to false to only return orders where send_date is equal to date_created.
+    pub scheduled: Option<bool>,
+    /// Filter by ISO-8601 date or datetime, e.g. { gt: '2012-01-01', lt: '2012-01-31T12:34:56Z' } where gt is ›, lt is ‹, gte is ≥, and lte is ≤.
+    pub send_date: Option<DateFilter>,
+    pub mail_type: Option<MailType>,
+    /// Sorts self-mailers in a desired order. sort_by accepts an object with the key being either date_created or send_date and the value being either asc or desc.
+    pub sort_by: Option<SortBy>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Letter {
-    pub id: String,
+    pub id: LetterId,
     pub description: Option<String>,
     pub metadata: BTreeMap<String, String>,
     pub to: Address,
@@ -548,8 +724,8 @@ pub struct Letter {
     pub mail_type: MailType,
     pub url: String,
     pub merge_variables: Option<BTreeMap<String, String>>,
-    pub template_id: Option<String>,
-    pub template_version_id: Option<String>,
+    pub template_id: Option<TemplateId>,
+    pub template_version_id: Option<TemplateVersionId>,
     pub carrier: String,
     pub tracking_number: Option<String>,
     pub tracking_events: Vec<TrackingEvent>,
@@ -609,7 +785,7 @@ pub struct ListLetterOptions {
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Check {
-    pub id: String,
+    pub id: CheckId,
     pub description: Option<String>,
     pub metadata: BTreeMap<String, String>,
     pub check_number: i32,
@@ -617,10 +793,10 @@ pub struct Check {
     pub amount: CheckAmount, //TODO should we use a third-party crate (e.g. bigdecimal)?
     pub message: Option<String>,
     pub url: String,
-    pub check_bottom_template_id: Option<String>,
-    pub attachment_template_id: Option<String>,
-    pub check_bottom_template_version_id: Option<String>,
-    pub attachment_template_version_id: Option<String>,
+    pub check_bottom_template_id: Option<TemplateId>,
+    pub attachment_template_id: Option<TemplateId>,
+    pub check_bottom_template_version_id: Option<TemplateVersionId>,
+    pub attachment_template_version_id: Option<TemplateVersionId>,
     pub to: Address,
     pub from: Address,
     pub bank_account: BankAccount,
@@ -643,7 +819,7 @@ pub struct NewCheck {
     pub description: Option<String>,
     pub to: SendAddress,
     pub from: SendAddress,
-    pub bank_account: String,
+    pub bank_account: BankAccountId,
     pub amount: CheckAmount, //TODO should we create a custom type for this? Or use a third-party crate (e.g. bigdecimal)?
     pub memo: Option<String>,
     pub check_number: Option<i32>,
@@ -696,7 +872,7 @@ pub struct ListCheckOptions {
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct BankAccount {
-    pub id: String,
+    pub id: BankAccountId,
     pub description: Option<String>,
     pub metadata: BTreeMap<String, String>,
     pub routing_number: String,
@@ -724,7 +900,50 @@ pub struct NewBankAccount {
 
 #[derive(Debug, Clone, Default, Serialize)]
 pub struct ListBankAccountOptions {
-    pub limit: Option<u32>,
+    pub limit: Option<i32>,
+    pub after: Option<String>,
+    pub before: Option<String>,
+    pub include: Option<Vec<ListIncludeOptions>>,
+    pub metadata: Option<BTreeMap<String, String>>,
+    pub date_created: Option<DateFilter>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Template {
+    pub id: TemplateId,
+    pub description: Option<String>,
+    pub versions: Vec<TemplateVersion>,
+    /// The version used when the template is referenced by its ID.
+    pub published_version: TemplateVersion,
+    pub metadata: BTreeMap<String, String>,
+    pub date_created: DateTime<Utc>,
+    pub date_modified: DateTime<Utc>,
+    pub deleted: Option<bool>,
+    object: object::Template,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NewTemplate {
+    pub description: Option<String>,
+    /// The HTML of the template's first version, which is published right away.
+    pub html: String,
+    pub engine: Option<TemplateEngine>,
+    pub metadata: Option<BTreeMap<String, String>>,
+}
+
+/// Changes to a template; fields left as `None` are not changed.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UpdateTemplate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Publish this version of the template.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub published_version: Option<TemplateVersionId>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ListTemplateOptions {
+    pub limit: Option<i32>,
     pub after: Option<String>,
     pub before: Option<String>,
     pub include: Option<Vec<ListIncludeOptions>>,
@@ -732,6 +951,260 @@ pub struct ListBankAccountOptions {
     pub date_created: Option<DateFilter>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TemplateVersion {
+    pub id: TemplateVersionId,
+    pub description: Option<String>,
+    pub html: String,
+    pub engine: Option<TemplateEngine>,
+    pub merge_variables: Option<TemplateMergeVariables>,
+    pub date_created: DateTime<Utc>,
+    pub date_modified: DateTime<Utc>,
+    pub deleted: Option<bool>,
+    object: object::TemplateVersion,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TemplateMergeVariables {
+    /// The merge variables used by the version's HTML.
+    pub keys: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NewTemplateVersion {
+    pub description: Option<String>,
+    pub html: String,
+    pub engine: Option<TemplateEngine>,
+}
+
+/// Changes to a template version; fields left as `None` are not changed.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UpdateTemplateVersion {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub engine: Option<TemplateEngine>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ListTemplateVersionOptions {
+    pub limit: Option<i32>,
+    pub after: Option<String>,
+    pub before: Option<String>,
+    pub include: Option<Vec<ListIncludeOptions>>,
+    pub date_created: Option<DateFilter>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Campaign {
+    pub id: CampaignId,
+    pub name: String,
+    pub description: Option<String>,
+    pub billing_group_id: Option<String>,
+    pub schedule_type: ScheduleType,
+    pub target_delivery_date: Option<DateTime<Utc>>,
+    pub send_date: Option<DateTime<Utc>>,
+    /// How long after sending the campaign can still be canceled.
+    pub cancel_window_campaign_minutes: Option<u32>,
+    pub use_type: Option<UseType>,
+    pub auto_cancel_if_ncoa: Option<bool>,
+    /// `false` once the campaign has been sent.
+    pub is_draft: bool,
+    #[serde(default)]
+    pub metadata: BTreeMap<String, String>,
+    pub date_created: DateTime<Utc>,
+    pub date_modified: DateTime<Utc>,
+    pub deleted: Option<bool>,
+    object: object::Campaign,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NewCampaign {
+    pub name: String,
+    pub description: Option<String>,
+    pub billing_group_id: Option<String>,
+    pub schedule_type: ScheduleType,
+    pub target_delivery_date: Option<DateTime<Utc>>,
+    pub send_date: Option<DateTime<Utc>>,
+    pub cancel_window_campaign_minutes: Option<u32>,
+    pub use_type: Option<UseType>,
+    pub auto_cancel_if_ncoa: Option<bool>,
+    pub metadata: Option<BTreeMap<String, String>>,
+}
+
+/// Changes to a campaign that has not been sent; fields left as `None` are not changed.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UpdateCampaign {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schedule_type: Option<ScheduleType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_delivery_date: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub send_date: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cancel_window_campaign_minutes: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<BTreeMap<String, String>>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ListCampaignOptions {
+    pub limit: Option<i32>,
+    pub after: Option<String>,
+    pub before: Option<String>,
+    pub include: Option<Vec<ListIncludeOptions>>,
+}
+
+/// The artwork and print options of the mail pieces sent to a campaign's audience.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Creative {
+    pub id: CreativeId,
+    pub description: Option<String>,
+    pub resource_type: CreativeResourceType,
+    /// The print options of `resource_type`, e.g. `{"size": "6x9"}` for postcards.
+    #[serde(default)]
+    pub details: serde_json::Value,
+    #[serde(default)]
+    pub metadata: BTreeMap<String, String>,
+    pub date_created: DateTime<Utc>,
+    pub date_modified: DateTime<Utc>,
+    pub deleted: Option<bool>,
+    object: object::Creative,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NewCreative {
+    pub campaign_id: CampaignId,
+    pub resource_type: CreativeResourceType,
+    pub from: SendAddress,
+    pub description: Option<String>,
+    /// The front of postcard creatives.
+    #[serde(skip_serializing_if = "is_none_or_file")]
+    pub front: Option<FileInput>,
+    /// The back of postcard creatives.
+    #[serde(skip_serializing_if = "is_none_or_file")]
+    pub back: Option<FileInput>,
+    /// The content of letter creatives.
+    #[serde(skip_serializing_if = "is_none_or_file")]
+    pub file: Option<FileInput>,
+    /// The print options of `resource_type`, e.g. `{"size": "6x9"}` for postcards.
+    pub details: Option<serde_json::Value>,
+    pub metadata: Option<BTreeMap<String, String>>,
+}
+
+/// Creative files are uploaded as form parts, so are left out along with missing ones.
+fn is_none_or_file(file: &Option<FileInput>) -> bool {
+    match file {
+        Some(file) => file.is_file(),
+        None => true,
+    }
+}
+
+/// Changes to a creative; fields left as `None` are not changed.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UpdateCreative {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<SendAddress>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<BTreeMap<String, String>>,
+}
+
+/// The audience of a campaign, uploaded as a CSV file with one mail piece per row.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Upload {
+    pub id: UploadId,
+    pub campaign_id: CampaignId,
+    pub state: UploadState,
+    pub original_filename: Option<String>,
+    pub total_mailpieces: Option<u64>,
+    pub validated_mailpieces: Option<u64>,
+    pub failed_mailpieces: Option<u64>,
+    /// A CSV of the rows that failed validation, once the upload has been processed.
+    pub failures_url: Option<String>,
+    pub required_address_column_mapping: Option<RequiredAddressColumnMapping>,
+    pub optional_address_column_mapping: Option<OptionalAddressColumnMapping>,
+    pub merge_variable_column_mapping: Option<BTreeMap<String, String>>,
+    #[serde(default)]
+    pub metadata: BTreeMap<String, String>,
+    pub date_created: DateTime<Utc>,
+    pub date_modified: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewUpload {
+    pub campaign_id: CampaignId,
+    pub required_address_column_mapping: Option<RequiredAddressColumnMapping>,
+    pub optional_address_column_mapping: Option<OptionalAddressColumnMapping>,
+    /// Maps merge variable names to the CSV columns holding their values.
+    pub merge_variable_column_mapping: Option<BTreeMap<String, String>>,
+    pub metadata: Option<BTreeMap<String, String>>,
+}
+
+/// The CSV columns holding each part of the recipient's address.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RequiredAddressColumnMapping {
+    pub name: Option<String>,
+    pub address_line1: Option<String>,
+    pub address_city: Option<String>,
+    pub address_state: Option<String>,
+    pub address_zip: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct OptionalAddressColumnMapping {
+    pub company: Option<String>,
+    pub address_line2: Option<String>,
+    pub address_country: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct UploadedFile {
+    pub message: String,
+    pub filename: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct NewUploadExport {
+    pub message: String,
+    pub export_id: UploadExportId,
+}
+
+/// A CSV report of an upload's rows, e.g. those that failed validation.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadExport {
+    pub id: UploadExportId,
+    pub upload_id: UploadId,
+    #[serde(rename = "type")]
+    pub export_type: ExportType,
+    pub state: ExportState,
+    /// Where the report can be downloaded once `state` is `Succeeded`.
+    pub s3_url: Option<String>,
+    pub date_created: DateTime<Utc>,
+    pub date_modified: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ListUploadsQuery<'a> {
+    pub campaign_id: &'a CampaignId,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct UploadExportBody {
+    #[serde(rename = "type")]
+    pub export_type: ExportType,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct CustomEnvelope {
     pub id: String,
@@ -751,15 +1224,167 @@ pub struct TrackingEvent {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "RawEvent")]
 pub struct Event {
-    pub id: String,
-    pub body: Object,
+    pub id: EventId,
+    pub body: EventPayload,
     pub reference_id: String,
     pub event_type: EventType,
     pub date_created: DateTime<Utc>,
     object: object::Event,
 }
 
+impl Event {
+    pub fn as_postcard(&self) -> Option<&Postcard> {
+        self.body.as_postcard()
+    }
+
+    pub fn as_letter(&self) -> Option<&Letter> {
+        self.body.as_letter()
+    }
+
+    pub fn as_self_mailer(&self) -> Option<&SelfMailer> {
+        self.body.as_self_mailer()
+    }
+
+    pub fn as_check(&self) -> Option<&Check> {
+        self.body.as_check()
+    }
+
+    pub fn as_address(&self) -> Option<&Address> {
+        self.body.as_address()
+    }
+
+    pub fn as_bank_account(&self) -> Option<&BankAccount> {
+        self.body.as_bank_account()
+    }
+
+    pub fn as_deleted(&self) -> Option<&Delete> {
+        self.body.as_deleted()
+    }
+}
+
+#[derive(Deserialize)]
+struct RawEvent {
+    id: EventId,
+    body: serde_json::Value,
+    reference_id: String,
+    event_type: EventType,
+    date_created: DateTime<Utc>,
+    object: object::Event,
+}
+
+impl TryFrom<RawEvent> for Event {
+    type Error = serde_json::Error;
+
+    fn try_from(raw: RawEvent) -> Result<Self, Self::Error> {
+        Ok(Event {
+            body: EventPayload::decode(raw.body, &raw.event_type.resource)?,
+            id: raw.id,
+            reference_id: raw.reference_id,
+            event_type: raw.event_type,
+            date_created: raw.date_created,
+            object: raw.object,
+        })
+    }
+}
+
+/// The resource an event is about, decoded by the body's `object` field, or the event type's
+/// resource when the body has none.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum EventPayload {
+    Postcard(Box<Postcard>),
+    Letter(Box<Letter>),
+    SelfMailer(Box<SelfMailer>),
+    Check(Box<Check>),
+    Address(Box<Address>),
+    BankAccount(Box<BankAccount>),
+    /// A deleted resource of which only the ID remains, e.g. the body of `address.deleted`.
+    /// Canceled mail pieces keep their full body and variant instead.
+    Deleted(Delete),
+    /// A resource not known to this version of the crate, left as it was received.
+    Other(serde_json::Value),
+}
+
+impl EventPayload {
+    fn decode(body: serde_json::Value, resource: &Resource) -> Result<Self, serde_json::Error> {
+        use serde_json::from_value;
+        let resource = match body.get("object").and_then(|o| o.as_str()) {
+            Some("postcard") => &Resource::Postcards,
+            Some("letter") => &Resource::Letters,
+            Some("self_mailer") => &Resource::SelfMailers,
+            Some("check") => &Resource::Checks,
+            Some("address") => &Resource::Addresses,
+            Some("bank_account") => &Resource::BankAccounts,
+            Some(_) => resource,
+            // Canceled mail pieces keep their whole body, but deleted resources only their ID
+            None if body.get("deleted") == Some(&serde_json::Value::Bool(true)) => {
+                return Ok(EventPayload::Deleted(from_value(body)?));
+            }
+            None => resource,
+        };
+        Ok(match resource {
+            Resource::Postcards => EventPayload::Postcard(from_value(body)?),
+            Resource::Letters => EventPayload::Letter(from_value(body)?),
+            Resource::SelfMailers => EventPayload::SelfMailer(from_value(body)?),
+            Resource::Checks => EventPayload::Check(from_value(body)?),
+            Resource::Addresses => EventPayload::Address(from_value(body)?),
+            Resource::BankAccounts => EventPayload::BankAccount(from_value(body)?),
+            Resource::Unknown(_) => EventPayload::Other(body),
+        })
+    }
+
+    pub fn as_postcard(&self) -> Option<&Postcard> {
+        match self {
+            EventPayload::Postcard(postcard) => Some(postcard),
+            _ => None,
+        }
+    }
+
+    pub fn as_letter(&self) -> Option<&Letter> {
+        match self {
+            EventPayload::Letter(letter) => Some(letter),
+            _ => None,
+        }
+    }
+
+    pub fn as_self_mailer(&self) -> Option<&SelfMailer> {
+        match self {
+            EventPayload::SelfMailer(self_mailer) => Some(self_mailer),
+            _ => None,
+        }
+    }
+
+    pub fn as_check(&self) -> Option<&Check> {
+        match self {
+            EventPayload::Check(check) => Some(check),
+            _ => None,
+        }
+    }
+
+    pub fn as_address(&self) -> Option<&Address> {
+        match self {
+            EventPayload::Address(address) => Some(address),
+            _ => None,
+        }
+    }
+
+    pub fn as_bank_account(&self) -> Option<&BankAccount> {
+        match self {
+            EventPayload::BankAccount(bank_account) => Some(bank_account),
+            _ => None,
+        }
+    }
+
+    pub fn as_deleted(&self) -> Option<&Delete> {
+        match self {
+            EventPayload::Deleted(deleted) => Some(deleted),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EventType {
     pub id: EventTypeId,
@@ -768,187 +1393,237 @@ pub struct EventType {
     object: object::EventType,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
-pub enum EventTypeId {
-    // Postcards
-    /// Occurs when a postcard is successfully created (Lob returns a 200 status code).
-    #[serde(rename = "postcard.created")]
-    PostcardCreated,
-    /// Occurs when a postcard's PDF proof is successfully rendered.
-    #[serde(rename = "postcard.rendered_pdf")]
-    PostcardRenderedPdf,
-    /// Occurs when a postcard's thumbnails are successfully rendered.
-    #[serde(rename = "postcard.rendered_thumbnails")]
-    PostcardRenderedThumbnails,
-    /// Occurs when a postcard is successfully canceled.
-    #[serde(rename = "postcard.deleted")]
-    PostcardDeleted,
-    /// Occurs when a postcard receives a "Mailed" tracking event. Only enabled for certain Print & Mail Editions. Only created in the Live Environment.
-    #[serde(rename = "postcard.mailed")]
-    PostcardMailed,
-    /// Occurs when a postcard receives an "In Transit" tracking event. Only created in the Live Environment.
-    #[serde(rename = "postcard.in_transit")]
-    PostcardInTransit,
-    /// Occurs when a postcard receives an "In Local Area" tracking event. Only created in the Live Environment.
-    #[serde(rename = "postcard.in_local_area")]
-    PostcardInLocalArea,
-    /// Occurs when a postcard receives a "Processed for Delivery" tracking event. Only created in the Live Environment.
-    #[serde(rename = "postcard.processed_for_delivery")]
-    PostcardProcessedForDelivery,
-    /// Occurs when a postcard receives a "Re-Routed" tracking event. Only created in the Live Environment.
-    #[serde(rename = "postcard.re-routed")]
-    PostcardReRouted,
-    /// Occurs when a postcard receives a "Returned to Sender" tracking event. Only created in the Live Environment.
-    #[serde(rename = "postcard.returned_to_sender")]
-    PostcardReturnedToSender,
-
-    // Letters
-    /// Occurs when a letter is successfully created (Lob returns a 200 status code).
-    #[serde(rename = "letter.created")]
-    LetterCreated,
-    /// Occurs when a letter's PDF proof is successfully rendered.
-    #[serde(rename = "letter.rendered_pdf")]
-    LetterRenderedPdf,
-    /// Occurs when a letter's thumbnails are successfully rendered.
-    #[serde(rename = "letter.rendered_thumbnails")]
-    LetterRenderedThumbnails,
-    /// Occurs when a letter is successfully canceled.
-    #[serde(rename = "letter.deleted")]
-    LetterDeleted,
-    /// Occurs when a letter receives a "Mailed" tracking event. Only enabled for certain Print & Mail Editions. Only created in the Live Environment.
-    #[serde(rename = "letter.mailed")]
-    LetterMailed,
-    /// Occurs when a letter receives an "In Transit" tracking event. Only created in the Live Environment.
-    #[serde(rename = "letter.in_transit")]
-    LetterInTransit,
-    /// Occurs when a letter receives an "In Local Area" tracking event. Only created in the Live Environment.
-    #[serde(rename = "letter.in_local_area")]
-    LetterInLocalArea,
-    /// Occurs when a letter receives a "Processed for Delivery" tracking event. Only created in the Live Environment.
-    #[serde(rename = "letter.processed_for_delivery")]
-    LetterProcessedForDelivery,
-    /// Occurs when a letter receives a "Re-Routed" tracking event. Only created in the Live Environment.
-    #[serde(rename = "letter.re-routed")]
-    LetterReRouted,
-    /// Occurs when a letter receives a "Returned to Sender" tracking event. Only created in the Live Environment.
-    #[serde(rename = "letter.returned_to_sender")]
-    LetterReturnedToSender,
-
-    // Checks
-    /// Occurs when a check is successfully created (Lob returns a 200 status code).
-    #[serde(rename = "check.created")]
-    CheckCreated,
-    /// Occurs when a check's PDF proof is successfully rendered.
-    #[serde(rename = "check.rendered_pdf")]
-    CheckRenderedPdf,
-    /// Occurs when a check's thumbnails are successfully rendered.
-    #[serde(rename = "check.rendered_thumbnails")]
-    CheckRenderedThumbnails,
-    /// Occurs when a check is successfully canceled.
-    #[serde(rename = "check.deleted")]
-    CheckDeleted,
-    /// Occurs when a check receives an "In Transit" tracking event. Only created in the Live Environment.
-    #[serde(rename = "check.in_transit")]
-    CheckInTransit,
-    /// Occurs when a check receives an "In Local Area" tracking event. Only created in the Live Environment.
-    #[serde(rename = "check.in_local_area")]
-    CheckInLocalArea,
-    /// Occurs when a check receives a "Processed for Delivery" tracking event. Only created in the Live Environment.
-    #[serde(rename = "check.processed_for_delivery")]
-    CheckProcessedForDelivery,
-    /// Occurs when a check receives a "Re-Routed" tracking event. Only created in the Live Environment.
-    #[serde(rename = "check.re-routed")]
-    CheckReRouted,
-    /// Occurs when a check receives a "Returned to Sender" tracking event. Only created in the Live Environment.
-    #[serde(rename = "check.returned_to_sender")]
-    CheckReturnedToSender,
-
-    // Addresses
-    /// Occurs when an address is successfully created (Lob returns a 200 status code).
-    #[serde(rename = "address.created")]
-    AddressCreated,
-    /// Occurs when an address is successfully deleted.
-    #[serde(rename = "address.deleted")]
-    AddressDeleted,
-
-    // Bank Accounts
-    /// Occurs when a bank account is successfully created (Lob returns a 200 status code).
-    #[serde(rename = "bank_account.created")]
-    BankAccountCreated,
-    /// Occurs when a bank account is successfully deleted.
-    #[serde(rename = "bank_account.deleted")]
-    BankAccountDeleted,
-    /// Occurs when a bank account is successfully verified.
-    #[serde(rename = "bank_account.verified")]
-    BankAccountVerified,
+string_enum! {
+    pub enum EventTypeId {
+        // Postcards
+        /// Occurs when a postcard is successfully created (Lob returns a 200 status code).
+        PostcardCreated = "postcard.created",
+        /// Occurs when a postcard's PDF proof is successfully rendered.
+        PostcardRenderedPdf = "postcard.rendered_pdf",
+        /// Occurs when a postcard's thumbnails are successfully rendered.
+        PostcardRenderedThumbnails = "postcard.rendered_thumbnails",
+        /// Occurs when a postcard is successfully canceled.
+        PostcardDeleted = "postcard.deleted",
+        /// Occurs when a postcard receives a "Mailed" tracking event. Only enabled for certain Print & Mail Editions. Only created in the Live Environment.
+        PostcardMailed = "postcard.mailed",
+        /// Occurs when a postcard receives an "In Transit" tracking event. Only created in the Live Environment.
+        PostcardInTransit = "postcard.in_transit",
+        /// Occurs when a postcard receives an "In Local Area" tracking event. Only created in the Live Environment.
+        PostcardInLocalArea = "postcard.in_local_area",
+        /// Occurs when a postcard receives a "Processed for Delivery" tracking event. Only created in the Live Environment.
+        PostcardProcessedForDelivery = "postcard.processed_for_delivery",
+        /// Occurs when a postcard receives a "Re-Routed" tracking event. Only created in the Live Environment.
+        PostcardReRouted = "postcard.re-routed",
+        /// Occurs when a postcard receives a "Returned to Sender" tracking event. Only created in the Live Environment.
+        PostcardReturnedToSender = "postcard.returned_to_sender",
+
+        // Letters
+        /// Occurs when a letter is successfully created (Lob returns a 200 status code).
+        LetterCreated = "letter.created",
+        /// Occurs when a letter's PDF proof is successfully rendered.
+        LetterRenderedPdf = "letter.rendered_pdf",
+        /// Occurs when a letter's thumbnails are successfully rendered.
+        LetterRenderedThumbnails = "letter.rendered_thumbnails",
+        /// Occurs when a letter is successfully canceled.
+        LetterDeleted = "letter.deleted",
+        /// Occurs when a letter receives a "Mailed" tracking event. Only enabled for certain Print & Mail Editions. Only created in the Live Environment.
+        LetterMailed = "letter.mailed",
+        /// Occurs when a letter receives an "In Transit" tracking event. Only created in the Live Environment.
+        LetterInTransit = "letter.in_transit",
+        /// Occurs when a letter receives an "In Local Area" tracking event. Only created in the Live Environment.
+        LetterInLocalArea = "letter.in_local_area",
+        /// Occurs when a letter receives a "Processed for Delivery" tracking event. Only created in the Live Environment.
+        LetterProcessedForDelivery = "letter.processed_for_delivery",
+        /// Occurs when a letter receives a "Re-Routed" tracking event. Only created in the Live Environment.
+        LetterReRouted = "letter.re-routed",
+        /// Occurs when a letter receives a "Returned to Sender" tracking event. Only created in the Live Environment.
+        LetterReturnedToSender = "letter.returned_to_sender",
+
+        // Self-mailers
+        /// Occurs when a self-mailer is successfully created (Lob returns a 200 status code).
+        SelfMailerCreated = "self_mailer.created",
+        /// Occurs when a self-mailer's PDF proof is successfully rendered.
+        SelfMailerRenderedPdf = "self_mailer.rendered_pdf",
+        /// Occurs when a self-mailer's thumbnails are successfully rendered.
+        SelfMailerRenderedThumbnails = "self_mailer.rendered_thumbnails",
+        /// Occurs when a self-mailer is successfully canceled.
+        SelfMailerDeleted = "self_mailer.deleted",
+        /// Occurs when a self-mailer receives a "Mailed" tracking event. Only enabled for certain Print & Mail Editions. Only created in the Live Environment.
+        SelfMailerMailed = "self_mailer.mailed",
+        /// Occurs when a self-mailer receives an "In Transit" tracking event. Only created in the Live Environment.
+        SelfMailerInTransit = "self_mailer.in_transit",
+        /// Occurs when a self-mailer receives an "In Local Area" tracking event. Only created in the Live Environment.
+        SelfMailerInLocalArea = "self_mailer.in_local_area",
+        /// Occurs when a self-mailer receives a "Processed for Delivery" tracking event. Only created in the Live Environment.
+        SelfMailerProcessedForDelivery = "self_mailer.processed_for_delivery",
+        /// Occurs when a self-mailer receives a "Re-Routed" tracking event. Only created in the Live Environment.
+        SelfMailerReRouted = "self_mailer.re-routed",
+        /// Occurs when a self-mailer receives a "Returned to Sender" tracking event. Only created in the Live Environment.
+        SelfMailerReturnedToSender = "self_mailer.returned_to_sender",
+
+        // Checks
+        /// Occurs when a check is successfully created (Lob returns a 200 status code).
+        CheckCreated = "check.created",
+        /// Occurs when a check's PDF proof is successfully rendered.
+        CheckRenderedPdf = "check.rendered_pdf",
+        /// Occurs when a check's thumbnails are successfully rendered.
+        CheckRenderedThumbnails = "check.rendered_thumbnails",
+        /// Occurs when a check is successfully canceled.
+        CheckDeleted = "check.deleted",
+        /// Occurs when a check receives an "In Transit" tracking event. Only created in the Live Environment.
+        CheckInTransit = "check.in_transit",
+        /// Occurs when a check receives an "In Local Area" tracking event. Only created in the Live Environment.
+        CheckInLocalArea = "check.in_local_area",
+        /// Occurs when a check receives a "Processed for Delivery" tracking event. Only created in the Live Environment.
+        CheckProcessedForDelivery = "check.processed_for_delivery",
+        /// Occurs when a check receives a "Re-Routed" tracking event. Only created in the Live Environment.
+        CheckReRouted = "check.re-routed",
+        /// Occurs when a check receives a "Returned to Sender" tracking event. Only created in the Live Environment.
+        CheckReturnedToSender = "check.returned_to_sender",
+
+        // Addresses
+        /// Occurs when an address is successfully created (Lob returns a 200 status code).
+        AddressCreated = "address.created",
+        /// Occurs when an address is successfully deleted.
+        AddressDeleted = "address.deleted",
+
+        // Bank Accounts
+        /// Occurs when a bank account is successfully created (Lob returns a 200 status code).
+        BankAccountCreated = "bank_account.created",
+        /// Occurs when a bank account is successfully deleted.
+        BankAccountDeleted = "bank_account.deleted",
+        /// Occurs when a bank account is successfully verified.
+        BankAccountVerified = "bank_account.verified",
+    }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
-pub enum Resource {
-    Postcards,
-    Letters,
-    Checks,
-    Addresses,
-    BankAccounts,
+string_enum! {
+    pub enum Resource {
+        Postcards = "postcards",
+        Letters = "letters",
+        SelfMailers = "self_mailers",
+        Checks = "checks",
+        Addresses = "addresses",
+        BankAccounts = "bank_accounts",
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(untagged)]
-pub enum Object {
-    Address(Address),
-    Postcard(Postcard),
-    Letter(Letter),
-    Check(Check),
-    BankAccount(BankAccount),
-    Delete(Delete),
-}
-
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
-pub enum PostcardSize {
-    #[serde(rename = "4x6")]
-    FourBySix,
-    #[serde(rename = "6x9")]
-    SixByNine,
-    #[serde(rename = "6x11")]
-    SixByEleven,
-}
-
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
-#[serde(rename_all = "snake_case")]
-pub enum MailType {
-    UspsFirstClass,
-    UspsStandard,
-    UpsNextDayAir,
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct ListEventOptions {
+    /// An integer that designates how many results to return. Defaults to 10 and must be no more than 100.
+    pub limit: Option<i32>,
+    /// A reference to a list entry used for paginating to the next set of entries. This field is pre-populated in the next_url field in the return response.
+    pub after: Option<String>,
+    /// A reference to a list entry used for paginating to the previous set of entries. This field is pre-populated in the previous_url field in the return response.
+    pub before: Option<String>,
+    /// Request that the response include the total count by specifying include[]=total_count.
+    pub include: Option<Vec<ListIncludeOptions>>,
+    /// Only return events of this type, e.g. `letter.returned_to_sender`.
+    pub event_type_id: Option<EventTypeId>,
+    /// Only return events about this kind of resource.
+    pub resource: Option<Resource>,
+    /// Filter by ISO-8601 date or datetime, e.g. { gt: '2012-01-01', lt: '2012-01-31T12:34:56Z' } where gt is ›, lt is ‹, gte is ≥, and lte is ≤.
+    pub date_created: Option<DateFilter>,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
-pub enum UseType {
-    Marketing,
-    Operational,
+string_enum! {
+    pub enum PostcardSize {
+        FourBySix = "4x6",
+        SixByNine = "6x9",
+        SixByEleven = "6x11",
+    }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
-#[serde(rename_all = "snake_case")]
-pub enum LetterAddressPlacement {
-    TopFirstPage,
-    InsertBlankPage,
+string_enum! {
+    pub enum SelfMailerSize {
+        SixByEighteenBifold = "6x18_bifold",
+        ElevenByNineBifold = "11x9_bifold",
+        TwelveByNineBifold = "12x9_bifold",
+    }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
-#[serde(rename_all = "snake_case")]
-pub enum ExtraService {
-    Certified,
-    CertifiedReturnReceipt,
-    Registered,
+string_enum! {
+    pub enum MailType {
+        UspsFirstClass = "usps_first_class",
+        UspsStandard = "usps_standard",
+        UpsNextDayAir = "ups_next_day_air",
+    }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
-#[serde(rename_all = "snake_case")]
-pub enum AccountType {
-    Company,
-    Individual,
+string_enum! {
+    pub enum UseType {
+        Marketing = "marketing",
+        Operational = "operational",
+    }
+}
+
+string_enum! {
+    pub enum LetterAddressPlacement {
+        TopFirstPage = "top_first_page",
+        InsertBlankPage = "insert_blank_page",
+    }
+}
+
+string_enum! {
+    pub enum ExtraService {
+        Certified = "certified",
+        CertifiedReturnReceipt = "certified_return_receipt",
+        Registered = "registered",
+    }
+}
+
+string_enum! {
+    pub enum ScheduleType {
+        Immediate = "immediate",
+    }
+}
+
+string_enum! {
+    pub enum CreativeResourceType {
+        Letter = "letter",
+        Postcard = "postcard",
+    }
+}
+
+string_enum! {
+    pub enum UploadState {
+        Draft = "Draft",
+        ReadyForValidation = "Ready for Validation",
+        Validating = "Validating",
+        Scheduled = "Scheduled",
+        Cancelled = "Cancelled",
+        Errored = "Errored",
+    }
+}
+
+string_enum! {
+    pub enum ExportType {
+        All = "all",
+        Failures = "failures",
+        Successes = "successes",
+    }
+}
+
+string_enum! {
+    pub enum ExportState {
+        InProgress = "in_progress",
+        Failed = "failed",
+        Succeeded = "succeeded",
+    }
+}
+
+string_enum! {
+    pub enum TemplateEngine {
+        Legacy = "legacy",
+        Handlebars = "handlebars",
+    }
+}
+
+string_enum! {
+    pub enum AccountType {
+        Company = "company",
+        Individual = "individual",
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -966,11 +1641,14 @@ pub struct LobErrorResponse {
 pub struct LobError {
     pub message: String,
     pub status_code: i32,
+    /// A machine readable code such as `failed_deliverability_strictness`, if Lob returned one.
+    #[serde(default)]
+    pub code: Option<String>,
 }
 
 #[derive(Debug, Clone, Default, Serialize)]
 pub struct ListAddressesOptions {
-    pub limit: Option<u32>,
+    pub limit: Option<i32>,
     pub after: Option<String>,
     pub before: Option<String>,
     pub include: Option<Vec<ListIncludeOptions>>,
@@ -990,8 +1668,64 @@ pub struct ListResponse<T> {
     pub next_url: Option<String>,
     pub previous_url: Option<String>,
     pub count: u32,
+    /// Only present when requested with `ListIncludeOptions::TotalCount`.
+    #[serde(default)]
+    pub total_count: Option<u64>,
 }
 
+impl<T> ListResponse<T> {
+    /// The `after` value for requesting the next page, parsed out of `next_url`.
+    pub fn next_cursor(&self) -> Option<String> {
+        cursor(self.next_url.as_deref()?, "after")
+    }
+
+    /// The `before` value for requesting the previous page, parsed out of `previous_url`.
+    pub fn previous_cursor(&self) -> Option<String> {
+        cursor(self.previous_url.as_deref()?, "before")
+    }
+}
+
+// `next_url` and `previous_url` may be absolute or relative to the API
+fn cursor(url: &str, param: &str) -> Option<String> {
+    let base = reqwest::Url::parse(crate::DEFAULT_BASE_URL).ok()?;
+    base.join(url)
+        .ok()?
+        .query_pairs()
+        .find(|(key, _)| key == param)
+        .map(|(_, cursor)| cursor.into_owned())
+}
+
+macro_rules! list_options {
+    ($($options:ident),*) => {
+        $(
+            impl $options {
+                /// These options advanced to the page after `page`, or `None` if `page` is the
+                /// last one.
+                pub fn next_page_options<T>(&self, page: &ListResponse<T>) -> Option<$options> {
+                    Some($options {
+                        after: Some(page.next_cursor()?),
+                        before: None,
+                        ..self.clone()
+                    })
+                }
+            }
+        )*
+    };
+}
+
+list_options!(
+    ListAddressesOptions,
+    ListPostcardOptions,
+    ListLetterOptions,
+    ListSelfMailerOptions,
+    ListCheckOptions,
+    ListBankAccountOptions,
+    ListTemplateOptions,
+    ListTemplateVersionOptions,
+    ListCampaignOptions,
+    ListEventOptions
+);
+
 #[derive(Debug, Clone, Serialize)]
 pub struct DateFilter {
     pub gt: Option<DateTime<Utc>>,
@@ -1008,21 +1742,55 @@ impl fmt::Display for ListIncludeOptions {
     }
 }
 
+/// Options that apply to a single request rather than to its payload.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RequestOptions {
+    /// Sent as the `Idempotency-Key` header. Lob returns the original response for a repeated key
+    /// instead of creating (and mailing) a second resource, so a key of your own, e.g. an order
+    /// ID, stays stable across retries and resumed jobs.
+    pub idempotency_key: Option<String>,
+    /// Send a random `Idempotency-Key` when `idempotency_key` is not set. It is generated once
+    /// per call and reused by that call's retries only, so identical pieces sent by separate
+    /// calls are still both mailed.
+    pub generate_idempotency_key: bool,
+}
+
+impl RequestOptions {
+    pub fn with_idempotency_key<S: Into<String>>(key: S) -> RequestOptions {
+        RequestOptions {
+            idempotency_key: Some(key.into()),
+            ..RequestOptions::default()
+        }
+    }
+
+    pub fn with_generated_idempotency_key() -> RequestOptions {
+        RequestOptions {
+            generate_idempotency_key: true,
+            ..RequestOptions::default()
+        }
+    }
+}
+
+
+
 // TODO should this should handle files via AsyncRead or w/e?
 #[derive(Debug, Clone)]
 pub enum FileInput {
-    TemplateId(String),
+    TemplateId(TemplateId),
     Url(String),
     Html(String),
     File { filename: String, data: Vec<u8> },
 }
 
+impl From<TemplateId> for FileInput {
+    fn from(id: TemplateId) -> FileInput {
+        FileInput::TemplateId(id)
+    }
+}
+
 impl FileInput {
     pub fn is_file(&self) -> bool {
-        match self {
-            FileInput::File { .. } => true,
-            _ => false,
-        }
+        matches!(self, FileInput::File { .. })
     }
 
     pub fn is_maybe_file(file: &Option<FileInput>) -> bool {
@@ -1030,10 +1798,7 @@ impl FileInput {
     }
 
     pub fn is_url(&self) -> bool {
-        match self {
-            FileInput::Url { .. } => true,
-            _ => false,
-        }
+        matches!(self, FileInput::Url { .. })
     }
 }
 
@@ -1046,7 +1811,7 @@ impl CheckAmount {
         CheckAmount(dollars * 100 + cents)
     }
 
-    pub fn cents(cents: u64) -> CheckAmount {
+    pub const fn cents(cents: u64) -> CheckAmount {
         CheckAmount(cents)
     }
 
@@ -1132,14 +1897,11 @@ impl Serialize for FileInput {
         S: Serializer,
     {
         match self {
-            FileInput::TemplateId(s) | FileInput::Url(s) | FileInput::Html(s) => {
-                String::serialize(s, serializer)
-            }
-            FileInput::File { .. } => {
-                return Err(S::Error::custom(
-                    "BUG! field must be skipped if variant is File",
-                ))
-            }
+            FileInput::TemplateId(id) => id.serialize(serializer),
+            FileInput::Url(s) | FileInput::Html(s) => String::serialize(s, serializer),
+            FileInput::File { .. } => Err(S::Error::custom(
+                "BUG! field must be skipped if variant is File",
+            )),
         }
     }
 }
@@ -1165,7 +1927,7 @@ pub mod object {
                     D: serde::Deserializer<'de>,
                 {
                     use serde::de::Error;
-                    let s: &'de str = serde::Deserialize::deserialize(deserializer)?;
+                    let s: String = serde::Deserialize::deserialize(deserializer)?;
                     if s == $value {
                         Ok($name)
                     } else {
@@ -1186,8 +1948,13 @@ pub mod object {
     object_name!(InternationalVerification, "intl_verification");
     object_name!(Postcard, "postcard");
     object_name!(Letter, "letter");
+    object_name!(SelfMailer, "self_mailer");
     object_name!(Check, "check");
     object_name!(BankAccount, "bank_account");
+    object_name!(Template, "template");
+    object_name!(TemplateVersion, "version");
+    object_name!(Campaign, "campaign");
+    object_name!(Creative, "creative");
     object_name!(TrackingEvent, "tracking_event");
     object_name!(Event, "event");
     object_name!(EventType, "event_type");
@@ -1214,8 +1981,8 @@ mod yn_empty {
     where
         D: serde::Deserializer<'de>,
     {
-        let s: &'de str = Deserialize::deserialize(deserializer)?;
-        match s {
+        let s: String = Deserialize::deserialize(deserializer)?;
+        match s.as_str() {
             "Y" => Ok(Some(true)),
             "N" => Ok(Some(false)),
             "" => Ok(None),
@@ -1249,7 +2016,7 @@ mod none_as_empty_string {
         D: serde::Deserializer<'de>,
         T: Deserialize<'de>,
     {
-        let s: &'de str = Deserialize::deserialize(deserializer)?;
+        let s: String = Deserialize::deserialize(deserializer)?;
         if s.is_empty() {
             Ok(None)
         } else {
@@ -1257,3 +2024,223 @@ mod none_as_empty_string {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+    use serde_json::json;
+
+    macro_rules! string_enum_tests {
+        ($($test:ident: $name:ident::$variant:ident = $value:literal,)*) => {
+            $(
+                #[test]
+                fn $test() {
+                    let known = json!($value);
+                    assert_eq!(serde_json::from_value::<$name>(known.clone()).unwrap(), $name::$variant);
+                    assert_eq!(serde_json::to_value(&$name::$variant).unwrap(), known);
+
+                    let unknown = json!("added_later");
+                    let parsed = serde_json::from_value::<$name>(unknown.clone()).unwrap();
+                    assert_eq!(parsed, $name::Unknown("added_later".to_owned()));
+                    assert_eq!(serde_json::to_value(&parsed).unwrap(), unknown);
+                }
+            )*
+        };
+    }
+
+    string_enum_tests! {
+        deliverability: Deliverability::DeliverableMissingUnit = "deliverable_missing_unit",
+        zip_code_type: ZipCodeType::PoBox = "po_box",
+        address_type: AddressType::Commercial = "commercial",
+        record_type: RecordType::GeneralDelivery = "general_delivery",
+        carrier_route_type: CarrierRouteType::Contract = "contract",
+        dpv_confirmation: DpvConfirmation::S = "S",
+        dpv_code: DpvCode::RR = "RR",
+        lacs_return_code: LacsReturnCode::_92 = "92",
+        suite_return_code: SuiteReturnCode::_00 = "00",
+        event_type_id: EventTypeId::PostcardReRouted = "postcard.re-routed",
+        resource: Resource::BankAccounts = "bank_accounts",
+        postcard_size: PostcardSize::SixByEleven = "6x11",
+        self_mailer_size: SelfMailerSize::ElevenByNineBifold = "11x9_bifold",
+        mail_type: MailType::UpsNextDayAir = "ups_next_day_air",
+        use_type: UseType::Operational = "operational",
+        letter_address_placement: LetterAddressPlacement::InsertBlankPage = "insert_blank_page",
+        extra_service: ExtraService::CertifiedReturnReceipt = "certified_return_receipt",
+        account_type: AccountType::Individual = "individual",
+        template_engine: TemplateEngine::Handlebars = "handlebars",
+        schedule_type: ScheduleType::Immediate = "immediate",
+        creative_resource_type: CreativeResourceType::Postcard = "postcard",
+        upload_state: UploadState::ReadyForValidation = "Ready for Validation",
+        export_type: ExportType::Failures = "failures",
+        export_state: ExportState::InProgress = "in_progress",
+    }
+
+    #[test]
+    fn unknown_event_types_do_not_break_list_pages() {
+        let event = fixtures::event(
+            "evt_123",
+            "address.archived",
+            "addresses",
+            fixtures::address("adr_123"),
+        );
+        let page: ListResponse<Event> =
+            serde_json::from_value(fixtures::list(vec![event], None)).unwrap();
+        assert_eq!(
+            page.data[0].event_type.id,
+            EventTypeId::Unknown("address.archived".to_owned())
+        );
+    }
+
+    #[test]
+    fn decodes_deleted_events_in_list_pages() {
+        let deleted = fixtures::event(
+            "evt_456",
+            "address.deleted",
+            "addresses",
+            json!({ "id": "adr_123", "deleted": true }),
+        );
+        let created = fixtures::event(
+            "evt_123",
+            "address.created",
+            "addresses",
+            fixtures::address("adr_123"),
+        );
+        let page: ListResponse<Event> =
+            serde_json::from_value(fixtures::list(vec![deleted, created], None)).unwrap();
+        assert_eq!(page.data[0].event_type.id, EventTypeId::AddressDeleted);
+        assert_eq!(page.data[0].as_deleted().unwrap().id, "adr_123");
+        assert!(page.data[0].as_address().is_none());
+        assert_eq!(page.data[1].as_address().unwrap().id, "adr_123");
+    }
+
+    #[test]
+    fn decodes_canceled_mail_pieces_in_full() {
+        let mut letter = fixtures::letter("ltr_123");
+        letter["deleted"] = json!(true);
+        let event: Event = serde_json::from_value(fixtures::event(
+            "evt_789",
+            "letter.deleted",
+            "letters",
+            letter,
+        ))
+        .unwrap();
+        assert_eq!(event.event_type.id, EventTypeId::LetterDeleted);
+        let letter = event.as_letter().unwrap();
+        assert_eq!(letter.id.as_str(), "ltr_123");
+        assert_eq!(letter.deleted, Some(true));
+        assert!(event.as_deleted().is_none());
+    }
+
+    #[test]
+    fn unknown_values_in_empty_string_fields() {
+        #[derive(Deserialize)]
+        struct Components {
+            #[serde(with = "none_as_empty_string")]
+            carrier_route_type: Option<CarrierRouteType>,
+        }
+        let parse = |value: &str| {
+            serde_json::from_value::<Components>(json!({ "carrier_route_type": value }))
+                .unwrap()
+                .carrier_route_type
+        };
+        assert_eq!(parse(""), None);
+        assert_eq!(
+            parse("drone"),
+            Some(CarrierRouteType::Unknown("drone".to_owned()))
+        );
+    }
+
+    #[test]
+    fn decodes_event_body_by_object() {
+        let event: Event = serde_json::from_value(fixtures::event(
+            "evt_123",
+            "letter.returned_to_sender",
+            "letters",
+            fixtures::letter("ltr_123"),
+        ))
+        .unwrap();
+        assert_eq!(event.event_type.id, EventTypeId::LetterReturnedToSender);
+        assert_eq!(event.as_letter().unwrap().id, "ltr_123");
+        assert!(event.as_address().is_none());
+
+        // An address shares enough fields with other resources to be mistaken for them
+        let event: Event = serde_json::from_value(fixtures::event(
+            "evt_456",
+            "address.created",
+            "addresses",
+            fixtures::address("adr_123"),
+        ))
+        .unwrap();
+        assert_eq!(event.as_address().unwrap().id, "adr_123");
+    }
+
+    #[test]
+    fn decodes_self_mailer_events() {
+        let event: Event = serde_json::from_value(fixtures::event(
+            "evt_123",
+            "self_mailer.created",
+            "self_mailers",
+            fixtures::self_mailer("sfm_123"),
+        ))
+        .unwrap();
+        assert_eq!(event.event_type.id, EventTypeId::SelfMailerCreated);
+        assert_eq!(event.as_self_mailer().unwrap().id, "sfm_123");
+    }
+
+    #[test]
+    fn body_object_takes_precedence_over_resource() {
+        let event: Event = serde_json::from_value(fixtures::event(
+            "evt_123",
+            "letter.created",
+            "letters",
+            fixtures::address("adr_123"),
+        ))
+        .unwrap();
+        assert!(event.as_letter().is_none());
+        assert_eq!(event.as_address().unwrap().id, "adr_123");
+    }
+
+    #[test]
+    fn parses_cursors_and_total_count() {
+        let page: ListResponse<Delete> = serde_json::from_str(&json!({
+            "data": [{ "id": "ltr_1", "deleted": true }],
+            "object": "list",
+            "next_url": "https://api.lob.com/v1/letters?limit=1&after=eyJkYXRlT2Zmc2V0Ijoi%3D",
+            "previous_url": "/v1/letters?limit=1&before=eyJkYXRlT2Zmc2V0Ijoi",
+            "count": 1,
+            "total_count": 42
+        })
+        .to_string())
+        .unwrap();
+        assert_eq!(page.next_cursor().as_deref(), Some("eyJkYXRlT2Zmc2V0Ijoi="));
+        assert_eq!(page.previous_cursor().as_deref(), Some("eyJkYXRlT2Zmc2V0Ijoi"));
+        assert_eq!(page.total_count, Some(42));
+
+        let options = ListLetterOptions {
+            limit: Some(1),
+            before: Some("stale".into()),
+            ..ListLetterOptions::default()
+        };
+        let next = options.next_page_options(&page).unwrap();
+        assert_eq!(next.after.as_deref(), Some("eyJkYXRlT2Zmc2V0Ijoi="));
+        assert_eq!(next.before, None);
+        assert_eq!(next.limit, Some(1));
+    }
+
+    #[test]
+    fn last_page_has_no_next_options() {
+        let page: ListResponse<Delete> = serde_json::from_str(&json!({
+            "data": [],
+            "object": "list",
+            "next_url": null,
+            "previous_url": null,
+            "count": 0
+        })
+        .to_string())
+        .unwrap();
+        assert_eq!(page.next_cursor(), None);
+        assert_eq!(page.total_count, None);
+        assert!(ListAddressesOptions::default().next_page_options(&page).is_none());
+    }
+}
@@ -0,0 +1,133 @@
+use crate::error::Error;
+use crate::model::FileInput;
+use reqwest::multipart::{Form, Part};
+use serde::Serialize;
+use serde_json::Value;
+use std::mem;
+
+/// Local uploads taken out of a payload, to be sent as file parts next to its fields.
+#[derive(Debug, Default)]
+pub(crate) struct Files(Vec<(&'static str, String, Vec<u8>)>);
+
+impl Files {
+    pub fn take(&mut self, name: &'static str, input: &mut FileInput) {
+        if let FileInput::File { filename, data } = input {
+            self.0.push((name, mem::take(filename), mem::take(data)));
+        }
+    }
+
+    pub fn take_maybe(&mut self, name: &'static str, input: &mut Option<FileInput>) {
+        if let Some(input) = input {
+            self.take(name, input);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Sends `payload` as JSON, or as a single multipart form when there are files to upload. The
+/// files are copied into the form, so that it can be encoded again for each attempt.
+pub(crate) fn encode<T: Serialize>(
+    request: reqwest::RequestBuilder,
+    payload: &T,
+    files: &Files,
+) -> Result<reqwest::RequestBuilder, Error> {
+    if files.is_empty() {
+        return Ok(request.json(payload));
+    }
+    let mut form = Form::new();
+    for (name, value) in fields(payload)? {
+        form = form.text(name, value);
+    }
+    for (name, filename, data) in &files.0 {
+        form = form.part(*name, Part::bytes(data.clone()).file_name(filename.clone()));
+    }
+    Ok(request.multipart(form))
+}
+
+/// The fields of the form `encode` sends for `payload`, without the files, or `None` when it is
+/// sent as JSON. A cassette matches multipart requests on these, since their bodies are streamed.
+pub(crate) fn form_fields<T: Serialize>(
+    payload: &T,
+    files: &Files,
+) -> Result<Option<Value>, Error> {
+    if files.is_empty() {
+        return Ok(None);
+    }
+    let fields = fields(payload)?
+        .into_iter()
+        .map(|(name, value)| (name, Value::from(value)))
+        .collect();
+    Ok(Some(Value::Object(fields)))
+}
+
+/// Flattens `payload` into form fields, naming nested values the way Lob expects them,
+/// e.g. `to[address_line1]` or `metadata[customer_id]`. `None` fields are left out.
+pub(crate) fn fields<T: Serialize>(payload: &T) -> Result<Vec<(String, String)>, Error> {
+    let mut fields = Vec::new();
+    match serde_json::to_value(payload)? {
+        Value::Object(map) => {
+            for (key, value) in map {
+                flatten(key, value, &mut fields);
+            }
+        }
+        _ => return Err(Error::bad_request("multipart payload must be an object")),
+    }
+    Ok(fields)
+}
+
+fn flatten(name: String, value: Value, fields: &mut Vec<(String, String)>) {
+    match value {
+        Value::Null => {}
+        Value::String(s) => fields.push((name, s)),
+        Value::Bool(_) | Value::Number(_) => fields.push((name, value.to_string())),
+        Value::Array(values) => {
+            for (i, value) in values.into_iter().enumerate() {
+                flatten(format!("{}[{}]", name, i), value, fields);
+            }
+        }
+        Value::Object(map) => {
+            for (key, value) in map {
+                flatten(format!("{}[{}]", name, key), value, fields);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn flattens_nested_fields() {
+        let fields = fields(&json!({
+            "description": "a postcard",
+            "to": { "name": "Jared Polis", "address_line2": null },
+            "metadata": { "key": "value" },
+            "merge_variables": { "name": "Jared", "amounts": [1, 2] },
+            "size": "4x6",
+            "color": true,
+            "send_date": null,
+        }))
+        .unwrap();
+        assert_eq!(
+            fields,
+            [
+                ("color", "true"),
+                ("description", "a postcard"),
+                ("merge_variables[amounts][0]", "1"),
+                ("merge_variables[amounts][1]", "2"),
+                ("merge_variables[name]", "Jared"),
+                ("metadata[key]", "value"),
+                ("size", "4x6"),
+                ("to[name]", "Jared Polis"),
+            ]
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,109 @@
+use crate::error::Error;
+use rand::Rng;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+type RetryHook = Arc<dyn Fn(&Error, u32) -> bool + Send + Sync>;
+
+/// Opt-in policy for retrying failed requests, see `ClientBuilder::retry_policy`.
+///
+/// A request is retried while attempts remain, `Error::is_retryable` holds and the optional
+/// `should_retry` hook does not veto it. `POST` requests are only retried when they carry an
+/// `Idempotency-Key`, since otherwise a retry could create a second resource.
+#[derive(Clone)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    jitter: bool,
+    should_retry: Option<RetryHook>,
+}
+
+impl RetryPolicy {
+    /// `max_attempts` counts the initial request, so `RetryPolicy::new(1)` never retries.
+    pub fn new(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: max_attempts.max(1),
+            ..RetryPolicy::default()
+        }
+    }
+
+    /// Backoff before the first retry, doubled for every retry after that.
+    pub fn initial_backoff(mut self, backoff: Duration) -> RetryPolicy {
+        self.initial_backoff = backoff;
+        self
+    }
+
+    /// Upper bound for a single backoff, including one requested through `Retry-After`.
+    pub fn max_backoff(mut self, backoff: Duration) -> RetryPolicy {
+        self.max_backoff = backoff;
+        self
+    }
+
+    /// Randomize each backoff between half and all of its computed value. Enabled by default.
+    pub fn jitter(mut self, jitter: bool) -> RetryPolicy {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Called with the error and the number of the attempt that failed; returning `false` vetoes
+    /// the retry.
+    pub fn should_retry<F>(mut self, hook: F) -> RetryPolicy
+    where
+        F: Fn(&Error, u32) -> bool + Send + Sync + 'static,
+    {
+        self.should_retry = Some(Arc::new(hook));
+        self
+    }
+
+    pub(crate) fn retries(&self, error: &Error, attempt: u32) -> bool {
+        attempt < self.max_attempts
+            && error.is_retryable()
+            && match &self.should_retry {
+                Some(hook) => hook(error, attempt),
+                None => true,
+            }
+    }
+
+    pub(crate) fn backoff(&self, error: &Error, attempt: u32) -> Duration {
+        if let Some(retry_after) = error.retry_after() {
+            return retry_after.min(self.max_backoff);
+        }
+        let exponent = attempt.saturating_sub(1).min(16);
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(1 << exponent)
+            .min(self.max_backoff);
+        if self.jitter {
+            let half = backoff / 2;
+            half + rand::thread_rng().gen_range(Duration::ZERO..=half)
+        } else {
+            backoff
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            jitter: true,
+            should_retry: None,
+        }
+    }
+}
+
+impl fmt::Debug for RetryPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_attempts", &self.max_attempts)
+            .field("initial_backoff", &self.initial_backoff)
+            .field("max_backoff", &self.max_backoff)
+            .field("jitter", &self.jitter)
+            .field("should_retry", &self.should_retry.is_some())
+            .finish()
+    }
+}
@@ -0,0 +1,166 @@
+use crate::error::Error;
+use crate::model::*;
+use futures::stream::{self, BoxStream, Stream, StreamExt, TryStreamExt};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// A stream over every item of a list endpoint, following `next_url` cursors page by page until
+/// the last one. Pages are only requested as the stream is polled, and `limit` in the list
+/// options is used as the page size.
+pub struct ListStream<'a, T> {
+    inner: BoxStream<'a, Result<T, Error>>,
+    remaining: Option<usize>,
+}
+
+impl<'a, T> ListStream<'a, T> {
+    /// End the stream after `max_items` items, without requesting any further pages.
+    pub fn max_items(mut self, max_items: usize) -> ListStream<'a, T> {
+        self.remaining = Some(max_items);
+        self
+    }
+}
+
+impl<T> Stream for ListStream<'_, T> {
+    type Item = Result<T, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.remaining == Some(0) {
+            return Poll::Ready(None);
+        }
+        let item = self.inner.poll_next_unpin(cx);
+        if let (Poll::Ready(Some(_)), Some(remaining)) = (&item, &mut self.remaining) {
+            *remaining -= 1;
+        }
+        item
+    }
+}
+
+/// List options that can be advanced to the page after a cursor.
+pub(crate) trait Paginate: Sized {
+    fn next_page_options<T>(&self, page: &ListResponse<T>) -> Option<Self>;
+}
+
+macro_rules! paginate {
+    ($($options:ty),*) => {
+        $(
+            impl Paginate for $options {
+                fn next_page_options<T>(&self, page: &ListResponse<T>) -> Option<Self> {
+                    <$options>::next_page_options(self, page)
+                }
+            }
+        )*
+    };
+}
+
+paginate!(
+    ListAddressesOptions,
+    ListPostcardOptions,
+    ListLetterOptions,
+    ListSelfMailerOptions,
+    ListCheckOptions,
+    ListBankAccountOptions,
+    ListTemplateOptions,
+    ListTemplateVersionOptions,
+    ListCampaignOptions,
+    ListEventOptions
+);
+
+pub(crate) fn paginate<'a, O, T, F, Fut>(options: O, fetch: F) -> ListStream<'a, T>
+where
+    O: Paginate + Clone + Send + 'a,
+    T: Send + 'a,
+    F: Fn(O) -> Fut + Send + 'a,
+    Fut: Future<Output = Result<ListResponse<T>, Error>> + Send + 'a,
+{
+    let pages = stream::try_unfold(Some(options), move |options: Option<O>| {
+        let request = options.map(|options| (fetch(options.clone()), options));
+        async move {
+            let (page, options) = match request {
+                Some(request) => request,
+                None => return Ok::<_, Error>(None),
+            };
+            let page = page.await?;
+            let next = if page.data.is_empty() {
+                None
+            } else {
+                options.next_page_options(&page)
+            };
+            Ok(Some((stream::iter(page.data.into_iter().map(Ok)), next)))
+        }
+    });
+    ListStream {
+        inner: pages.try_flatten().boxed(),
+        remaining: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{fixtures, Client};
+    use futures::TryStreamExt;
+    use tokio_test::block_on;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn page(ids: &[&str], next_url: Option<String>) -> ResponseTemplate {
+        let data = ids.iter().map(|id| fixtures::address(id)).collect();
+        ResponseTemplate::new(200).set_body_json(fixtures::list(data, next_url))
+    }
+
+    async fn server() -> MockServer {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/addresses/"))
+            .and(query_param("after", "cursor_1"))
+            .respond_with(page(&["adr_3"], None))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/addresses/"))
+            .respond_with(page(
+                &["adr_1", "adr_2"],
+                Some(format!("{}/addresses?limit=2&after=cursor_1", server.uri())),
+            ))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        server
+    }
+
+    fn client(server: &MockServer) -> Client {
+        Client::builder("test_key")
+            .base_url(server.uri())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn follows_cursors_until_the_last_page() {
+        block_on(async {
+            let server = server().await;
+            let addresses: Vec<_> = client(&server)
+                .list_addresses_stream(None)
+                .try_collect()
+                .await
+                .unwrap();
+            let ids: Vec<_> = addresses.iter().map(|a| a.id.as_str()).collect();
+            assert_eq!(ids, ["adr_1", "adr_2", "adr_3"]);
+        })
+    }
+
+    #[test]
+    fn stops_at_max_items() {
+        block_on(async {
+            let server = server().await;
+            let addresses: Vec<_> = client(&server)
+                .list_addresses_stream(None)
+                .max_items(2)
+                .try_collect()
+                .await
+                .unwrap();
+            assert_eq!(addresses.len(), 2);
+            assert_eq!(server.received_requests().await.unwrap().len(), 1);
+        })
+    }
+}
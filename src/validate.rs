@@ -0,0 +1,457 @@
+//! Checks of new mail pieces and addresses against the limits Lob enforces, so that a request Lob
+//! would reject fails without a round trip, and with every problem at once instead of the first.
+//!
+//! Only the limits that can be checked offline are covered, e.g. the length of `description` and
+//! the size of `metadata`, but not whether an address exists. A piece that passes may still be
+//! rejected by Lob.
+
+use crate::model::*;
+use std::collections::BTreeMap;
+use std::fmt;
+
+const MAX_DESCRIPTION_LEN: usize = 255;
+const MAX_METADATA_KEYS: usize = 20;
+const MAX_METADATA_KEY_LEN: usize = 40;
+const MAX_METADATA_VALUE_LEN: usize = 500;
+/// Of the merge variables serialized as JSON.
+const MAX_MERGE_VARIABLES_LEN: usize = 25_000;
+const MAX_HTML_LEN: usize = 10_000;
+const MAX_NAME_LEN: usize = 40;
+const MAX_ADDRESS_LINE_LEN: usize = 64;
+const MAX_PHONE_LEN: usize = 40;
+const MAX_EMAIL_LEN: usize = 100;
+const MAX_CHECK_MESSAGE_LEN: usize = 400;
+const MAX_CHECK_MEMO_LEN: usize = 40;
+/// $999,999.99
+const MAX_CHECK_AMOUNT: CheckAmount = CheckAmount::cents(99_999_999);
+
+/// A field that Lob would reject.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// The path of the field, e.g. `metadata.customer_id` or `to.address_line1`.
+    pub field: String,
+    pub message: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` {}", self.field, self.message)
+    }
+}
+
+/// Every `Violation` found in a piece, in the order of its fields.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    violations: Vec<Violation>,
+}
+
+impl ValidationError {
+    pub fn violations(&self) -> &[Violation] {
+        &self.violations
+    }
+
+    pub fn into_violations(self) -> Vec<Violation> {
+        self.violations
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, violation) in self.violations.iter().enumerate() {
+            if i > 0 {
+                f.write_str("; ")?;
+            }
+            violation.fmt(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct Violations(Vec<Violation>);
+
+impl Violations {
+    fn push<F: Into<String>, M: Into<String>>(&mut self, field: F, message: M) {
+        self.0.push(Violation {
+            field: field.into(),
+            message: message.into(),
+        });
+    }
+
+    fn max_len(&mut self, field: &str, value: Option<&str>, max: usize) {
+        let len = value.map_or(0, |value| value.chars().count());
+        if len > max {
+            self.push(
+                field,
+                format!("must be at most {} characters, but is {}", max, len),
+            );
+        }
+    }
+
+    fn metadata(&mut self, metadata: &Option<BTreeMap<String, String>>) {
+        let metadata = match metadata {
+            Some(metadata) => metadata,
+            None => return,
+        };
+        if metadata.len() > MAX_METADATA_KEYS {
+            self.push(
+                "metadata",
+                format!(
+                    "must have at most {} keys, but has {}",
+                    MAX_METADATA_KEYS,
+                    metadata.len()
+                ),
+            );
+        }
+        for (key, value) in metadata {
+            let field = format!("metadata.{}", key);
+            if key.chars().count() > MAX_METADATA_KEY_LEN {
+                self.push(
+                    field.clone(),
+                    format!("key must be at most {} characters", MAX_METADATA_KEY_LEN),
+                );
+            }
+            self.max_len(&field, Some(value), MAX_METADATA_VALUE_LEN);
+        }
+    }
+
+    fn merge_variables(&mut self, merge_variables: &Option<BTreeMap<String, String>>) {
+        let len = merge_variables
+            .as_ref()
+            .and_then(|merge_variables| serde_json::to_string(merge_variables).ok())
+            .map_or(0, |json| json.chars().count());
+        if len > MAX_MERGE_VARIABLES_LEN {
+            self.push(
+                "merge_variables",
+                format!(
+                    "must be at most {} characters as JSON, but is {}",
+                    MAX_MERGE_VARIABLES_LEN, len
+                ),
+            );
+        }
+    }
+
+    fn html(&mut self, field: &str, file: Option<&FileInput>) {
+        if let Some(FileInput::Html(html)) = file {
+            self.max_len(field, Some(html), MAX_HTML_LEN);
+        }
+    }
+
+    fn send_address(&mut self, field: &str, address: Option<&SendAddress>) {
+        if let Some(SendAddress::Components(address)) = address {
+            self.max_len(
+                &format!("{}.name", field),
+                Some(&address.name),
+                MAX_NAME_LEN,
+            );
+            self.max_len(
+                &format!("{}.address_line1", field),
+                Some(&address.address_line1),
+                MAX_ADDRESS_LINE_LEN,
+            );
+            self.max_len(
+                &format!("{}.address_line2", field),
+                address.address_line2.as_deref(),
+                MAX_ADDRESS_LINE_LEN,
+            );
+        }
+    }
+
+    fn mail_type(&mut self, mail_type: &Option<MailType>, allowed: &[MailType]) {
+        if let Some(mail_type) = mail_type {
+            if !allowed.contains(mail_type) {
+                self.push("mail_type", format!("cannot be `{}`", mail_type));
+            }
+        }
+    }
+
+    fn into_result(self) -> Result<(), ValidationError> {
+        if self.0.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationError { violations: self.0 })
+        }
+    }
+}
+
+fn dollars(amount: CheckAmount) -> String {
+    let (dollars, cents) = amount.to_dollars_and_cents();
+    format!("${}.{:02}", dollars, cents)
+}
+
+impl NewAddress {
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let mut violations = Violations::default();
+        violations.max_len(
+            "description",
+            self.description.as_deref(),
+            MAX_DESCRIPTION_LEN,
+        );
+        if self.name.is_none() && self.company.is_none() {
+            violations.push("name", "or `company` must be set");
+        }
+        violations.max_len("name", self.name.as_deref(), MAX_NAME_LEN);
+        violations.max_len("company", self.company.as_deref(), MAX_NAME_LEN);
+        violations.max_len("phone", self.phone.as_deref(), MAX_PHONE_LEN);
+        violations.max_len("email", self.email.as_deref(), MAX_EMAIL_LEN);
+        violations.max_len(
+            "address_line1",
+            Some(&self.address_line1),
+            MAX_ADDRESS_LINE_LEN,
+        );
+        violations.max_len(
+            "address_line2",
+            self.address_line2.as_deref(),
+            MAX_ADDRESS_LINE_LEN,
+        );
+        violations.metadata(&self.metadata);
+        violations.into_result()
+    }
+}
+
+impl NewPostcard {
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let mut violations = Violations::default();
+        violations.max_len(
+            "description",
+            self.description.as_deref(),
+            MAX_DESCRIPTION_LEN,
+        );
+        violations.send_address("to", Some(&self.to));
+        violations.send_address("from", self.from.as_ref());
+        violations.html("front", Some(&self.front));
+        violations.html("back", Some(&self.back));
+        violations.merge_variables(&self.merge_variables);
+        violations.mail_type(
+            &self.mail_type,
+            &[MailType::UspsFirstClass, MailType::UspsStandard],
+        );
+        violations.metadata(&self.metadata);
+        violations.into_result()
+    }
+}
+
+impl NewLetter {
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let mut violations = Violations::default();
+        violations.max_len(
+            "description",
+            self.description.as_deref(),
+            MAX_DESCRIPTION_LEN,
+        );
+        violations.send_address("to", Some(&self.to));
+        violations.send_address("from", Some(&self.from));
+        violations.html("file", Some(&self.file));
+        violations.merge_variables(&self.merge_variables);
+        if self.perforated_page.is_some() && self.return_envelope != Some(true) {
+            violations.push("perforated_page", "requires `return_envelope`");
+        }
+        violations.mail_type(
+            &self.mail_type,
+            &[MailType::UspsFirstClass, MailType::UspsStandard],
+        );
+        violations.metadata(&self.metadata);
+        violations.into_result()
+    }
+}
+
+impl NewSelfMailer {
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let mut violations = Violations::default();
+        violations.max_len(
+            "description",
+            self.description.as_deref(),
+            MAX_DESCRIPTION_LEN,
+        );
+        violations.send_address("to", Some(&self.to));
+        violations.send_address("from", self.from.as_ref());
+        violations.html("inside", Some(&self.inside));
+        violations.html("outside", Some(&self.outside));
+        violations.merge_variables(&self.merge_variables);
+        violations.mail_type(
+            &self.mail_type,
+            &[MailType::UspsFirstClass, MailType::UspsStandard],
+        );
+        violations.metadata(&self.metadata);
+        violations.into_result()
+    }
+}
+
+impl NewCheck {
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let mut violations = Violations::default();
+        violations.max_len(
+            "description",
+            self.description.as_deref(),
+            MAX_DESCRIPTION_LEN,
+        );
+        violations.send_address("to", Some(&self.to));
+        violations.send_address("from", Some(&self.from));
+        if self.amount > MAX_CHECK_AMOUNT {
+            violations.push(
+                "amount",
+                format!(
+                    "must be at most {}, but is {}",
+                    dollars(MAX_CHECK_AMOUNT),
+                    dollars(self.amount)
+                ),
+            );
+        }
+        violations.max_len("memo", self.memo.as_deref(), MAX_CHECK_MEMO_LEN);
+        violations.max_len("message", self.message.as_deref(), MAX_CHECK_MESSAGE_LEN);
+        violations.html("check_bottom", self.check_bottom.as_ref());
+        violations.html("attachment", self.attachment.as_ref());
+        violations.mail_type(
+            &self.mail_type,
+            &[MailType::UspsFirstClass, MailType::UpsNextDayAir],
+        );
+        violations.metadata(&self.metadata);
+        violations.into_result()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to() -> SendAddress {
+        SendAddress::Components(SendAddressComponents {
+            name: "Jared Polis".to_owned(),
+            address_line1: "200 E Colfax Ave".to_owned(),
+            address_line2: None,
+            address_city: "Denver".to_owned(),
+            address_state: "CO".to_owned(),
+            address_zip: "80203".to_owned(),
+            address_country: None,
+        })
+    }
+
+    fn fields(err: ValidationError) -> Vec<String> {
+        err.into_violations()
+            .into_iter()
+            .map(|violation| violation.field)
+            .collect()
+    }
+
+    #[test]
+    fn reports_every_violation_of_a_letter() {
+        let metadata = (0..21)
+            .map(|i| {
+                (
+                    format!("key_{}", i),
+                    "x".repeat(if i == 0 { 501 } else { 1 }),
+                )
+            })
+            .collect();
+        let letter = NewLetter::builder(to(), to(), FileInput::Html("a".repeat(10_001)))
+            .description("d".repeat(256))
+            .perforated_page(1u32)
+            .mail_type(MailType::UpsNextDayAir)
+            .metadata::<BTreeMap<_, _>>(metadata)
+            .build();
+
+        let err = letter.validate().unwrap_err();
+        assert_eq!(
+            err.violations()[0].to_string(),
+            "`description` must be at most 255 characters, but is 256"
+        );
+        assert_eq!(
+            fields(err),
+            vec![
+                "description",
+                "file",
+                "perforated_page",
+                "mail_type",
+                "metadata",
+                "metadata.key_0",
+            ]
+        );
+
+        let letter = NewLetter::builder(to(), to(), FileInput::Html("<html></html>".to_owned()))
+            .perforated_page(1u32)
+            .return_envelope(true)
+            .build();
+        assert_eq!(letter.validate(), Ok(()));
+    }
+
+    #[test]
+    fn validates_postcards_self_mailers_checks_and_addresses() {
+        let mut merge_variables = BTreeMap::new();
+        merge_variables.insert("name".to_owned(), "n".repeat(MAX_MERGE_VARIABLES_LEN));
+        let postcard = NewPostcard::builder(
+            to(),
+            FileInput::Url("https://example.com/front.pdf".to_owned()),
+            FileInput::Html("<html></html>".to_owned()),
+        )
+        .merge_variables(merge_variables)
+        .build();
+        assert_eq!(
+            fields(postcard.validate().unwrap_err()),
+            vec!["merge_variables"]
+        );
+
+        let self_mailer = NewSelfMailer {
+            description: None,
+            to: to(),
+            from: None,
+            inside: FileInput::Html("a".repeat(MAX_HTML_LEN + 1)),
+            outside: FileInput::Url("https://example.com/outside.pdf".to_owned()),
+            merge_variables: None,
+            size: None,
+            mail_type: Some(MailType::UpsNextDayAir),
+            use_type: None,
+            send_date: None,
+            metadata: None,
+        };
+        assert_eq!(
+            fields(self_mailer.validate().unwrap_err()),
+            vec!["inside", "mail_type"]
+        );
+
+        let check = NewCheck::builder(
+            to(),
+            to(),
+            "bank_8cad8df5354d33f".parse().unwrap(),
+            CheckAmount::new(1_000_000, 0),
+        )
+        .memo("m".repeat(41))
+        .mail_type(MailType::UspsStandard)
+        .message("Thanks!")
+        .build();
+        let err = check.validate().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "`amount` must be at most $999999.99, but is $1000000.00; \
+             `memo` must be at most 40 characters, but is 41; \
+             `mail_type` cannot be `usps_standard`"
+        );
+
+        let mut metadata = BTreeMap::new();
+        metadata.insert("k".repeat(41), "v".to_owned());
+        let address = NewAddress {
+            description: None,
+            name: None,
+            company: None,
+            phone: None,
+            email: None,
+            address_line1: "5600 Arapahoe Ave. STE 200".to_owned(),
+            address_line2: None,
+            address_city: Some("Boulder".to_owned()),
+            address_state: Some("CO".to_owned()),
+            address_zip: Some("80304".to_owned()),
+            address_country: Some("US".to_owned()),
+            metadata: Some(metadata),
+        };
+        let err = address.validate().unwrap_err();
+        assert_eq!(
+            err.violations()[0].to_string(),
+            "`name` or `company` must be set"
+        );
+        assert_eq!(
+            err.violations()[1].field,
+            format!("metadata.{}", "k".repeat(41))
+        );
+    }
+}
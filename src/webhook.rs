@@ -0,0 +1,303 @@
+//! Verification of the webhook deliveries Lob signs with your webhook secret.
+//!
+//! Lob sends `Lob-Signature`, the hex encoded HMAC-SHA256 of `{timestamp}.{body}`, and
+//! `Lob-Signature-Timestamp`, the time the delivery was signed. Deliveries whose timestamp is
+//! outside the tolerance window are rejected, so a captured delivery cannot be replayed later.
+
+pub use self::router::{Dispatch, HandlerError, HandlerFailure, Route, WebhookRouter};
+
+use crate::model::Event;
+use chrono::{DateTime, TimeZone, Utc};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::time::Duration;
+
+#[cfg(feature = "axum")]
+pub mod axum;
+mod router;
+
+pub const SIGNATURE_HEADER: &str = "Lob-Signature";
+pub const TIMESTAMP_HEADER: &str = "Lob-Signature-Timestamp";
+
+/// How far the signature timestamp may be from the current time by default.
+pub const DEFAULT_TOLERANCE: Duration = Duration::from_secs(5 * 60);
+
+// Timestamps above this are in milliseconds rather than seconds since the epoch
+const MAX_SECONDS_TIMESTAMP: i64 = 100_000_000_000;
+
+#[derive(Clone)]
+pub struct Webhook {
+    secret: String,
+    tolerance: Duration,
+}
+
+impl Webhook {
+    pub fn new<S: Into<String>>(secret: S) -> Webhook {
+        Webhook {
+            secret: secret.into(),
+            tolerance: DEFAULT_TOLERANCE,
+        }
+    }
+
+    /// How far the signature timestamp may be from the current time, in either direction.
+    pub fn tolerance(mut self, tolerance: Duration) -> Webhook {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Check `signature` against the raw request `body`, exactly as it was received.
+    pub fn verify(
+        &self,
+        signature: &str,
+        timestamp: &str,
+        body: &[u8],
+    ) -> Result<(), WebhookError> {
+        self.verify_at(signature, timestamp, body, Utc::now())
+    }
+
+    /// Verify the delivery, then parse its body.
+    pub fn construct_event(
+        &self,
+        signature: &str,
+        timestamp: &str,
+        body: &[u8],
+    ) -> Result<Event, WebhookError> {
+        self.verify(signature, timestamp, body)?;
+        serde_json::from_slice(body).map_err(WebhookError::MalformedPayload)
+    }
+
+    /// Verify the delivery using its `Lob-Signature` and `Lob-Signature-Timestamp` headers, then
+    /// parse its body.
+    pub fn construct_event_from_headers<H>(
+        &self,
+        headers: &H,
+        body: &[u8],
+    ) -> Result<Event, WebhookError>
+    where
+        H: WebhookHeaders + ?Sized,
+    {
+        let signature = headers
+            .header(SIGNATURE_HEADER)
+            .ok_or(WebhookError::MissingHeader(SIGNATURE_HEADER))?;
+        let timestamp = headers
+            .header(TIMESTAMP_HEADER)
+            .ok_or(WebhookError::MissingHeader(TIMESTAMP_HEADER))?;
+        self.construct_event(signature, timestamp, body)
+    }
+
+    fn verify_at(
+        &self,
+        signature: &str,
+        timestamp: &str,
+        body: &[u8],
+        now: DateTime<Utc>,
+    ) -> Result<(), WebhookError> {
+        let signed_at = parse_timestamp(timestamp)?;
+        let age = (now - signed_at).abs().to_std().unwrap_or(Duration::MAX);
+        if age > self.tolerance {
+            return Err(WebhookError::StaleTimestamp { age });
+        }
+        let signature =
+            hex::decode(signature.trim()).map_err(|_| WebhookError::InvalidSignature)?;
+        mac(&self.secret, timestamp, body)
+            .verify_slice(&signature)
+            .map_err(|_| WebhookError::InvalidSignature)
+    }
+}
+
+impl fmt::Debug for Webhook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Webhook")
+            .field("secret", &"[redacted]")
+            .field("tolerance", &self.tolerance)
+            .finish()
+    }
+}
+
+/// Case-insensitive access to the headers of a webhook delivery, so any HTTP framework's header
+/// type can be used with `WebhookRouter::handle`.
+pub trait WebhookHeaders {
+    fn header(&self, name: &str) -> Option<&str>;
+}
+
+impl WebhookHeaders for reqwest::header::HeaderMap {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.get(name).and_then(|value| value.to_str().ok())
+    }
+}
+
+impl WebhookHeaders for HashMap<String, String> {
+    fn header(&self, name: &str) -> Option<&str> {
+        find_header(self.iter(), name)
+    }
+}
+
+impl WebhookHeaders for BTreeMap<String, String> {
+    fn header(&self, name: &str) -> Option<&str> {
+        find_header(self.iter(), name)
+    }
+}
+
+impl<K: AsRef<str>, V: AsRef<str>> WebhookHeaders for [(K, V)] {
+    fn header(&self, name: &str) -> Option<&str> {
+        find_header(self.iter().map(|(k, v)| (k, v)), name)
+    }
+}
+
+fn find_header<'a, I, K, V>(headers: I, name: &str) -> Option<&'a str>
+where
+    I: IntoIterator<Item = (&'a K, &'a V)>,
+    K: AsRef<str> + 'a + ?Sized,
+    V: AsRef<str> + 'a + ?Sized,
+{
+    headers
+        .into_iter()
+        .find(|(key, _)| key.as_ref().eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_ref())
+}
+
+/// The `Lob-Signature` for `body` signed at `timestamp`, e.g. for posting fixtures in tests.
+pub fn sign(secret: &str, timestamp: &str, body: &[u8]) -> String {
+    hex::encode(mac(secret, timestamp, body).finalize().into_bytes())
+}
+
+fn mac(secret: &str, timestamp: &str, body: &[u8]) -> Hmac<Sha256> {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(timestamp.as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    mac
+}
+
+fn parse_timestamp(timestamp: &str) -> Result<DateTime<Utc>, WebhookError> {
+    let invalid = || WebhookError::InvalidTimestamp(timestamp.to_owned());
+    let n: i64 = timestamp.trim().parse().map_err(|_| invalid())?;
+    let signed_at = if n > MAX_SECONDS_TIMESTAMP {
+        Utc.timestamp_millis_opt(n)
+    } else {
+        Utc.timestamp_opt(n, 0)
+    };
+    signed_at.single().ok_or_else(invalid)
+}
+
+#[derive(Debug)]
+pub enum WebhookError {
+    /// A required header, e.g. `Lob-Signature`, was not sent.
+    MissingHeader(&'static str),
+    /// The signature does not match the body, or was not signed with this secret.
+    InvalidSignature,
+    /// The timestamp is not a number of seconds or milliseconds since the epoch.
+    InvalidTimestamp(String),
+    /// The delivery was signed too long ago (or too far in the future) to be accepted.
+    StaleTimestamp { age: Duration },
+    /// The signature is valid, but the body is not an `Event`.
+    MalformedPayload(serde_json::Error),
+}
+
+impl std::error::Error for WebhookError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            WebhookError::MalformedPayload(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for WebhookError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WebhookError::MissingHeader(name) => write!(f, "Lob webhook - missing {} header", name),
+            WebhookError::InvalidSignature => write!(f, "Lob webhook - invalid signature"),
+            WebhookError::InvalidTimestamp(timestamp) => {
+                write!(f, "Lob webhook - invalid timestamp {:?}", timestamp)
+            }
+            WebhookError::StaleTimestamp { age } => {
+                write!(f, "Lob webhook - timestamp is {}s old", age.as_secs())
+            }
+            WebhookError::MalformedPayload(err) => {
+                write!(f, "Lob webhook - malformed payload: {}", err)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    const SECRET: &str = "whsec_test";
+
+    fn delivery() -> (String, String, Vec<u8>) {
+        let timestamp = Utc::now().timestamp_millis().to_string();
+        let body = fixtures::event(
+            "evt_123",
+            "address.created",
+            "addresses",
+            fixtures::address("adr_123"),
+        )
+        .to_string()
+        .into_bytes();
+        (sign(SECRET, &timestamp, &body), timestamp, body)
+    }
+
+    #[test]
+    fn accepts_signed_deliveries() {
+        let (signature, timestamp, body) = delivery();
+        let event = Webhook::new(SECRET)
+            .construct_event(&signature, &timestamp, &body)
+            .unwrap();
+        assert_eq!(event.id, "evt_123");
+    }
+
+    #[test]
+    fn rejects_tampered_bodies_and_other_secrets() {
+        let (signature, timestamp, mut body) = delivery();
+        assert!(matches!(
+            Webhook::new("whsec_other").verify(&signature, &timestamp, &body),
+            Err(WebhookError::InvalidSignature)
+        ));
+        body.push(b' ');
+        assert!(matches!(
+            Webhook::new(SECRET).verify(&signature, &timestamp, &body),
+            Err(WebhookError::InvalidSignature)
+        ));
+        assert!(matches!(
+            Webhook::new(SECRET).verify("not hex", &timestamp, &body),
+            Err(WebhookError::InvalidSignature)
+        ));
+    }
+
+    #[test]
+    fn rejects_stale_timestamps() {
+        let timestamp = (Utc::now().timestamp() - 600).to_string();
+        let body = b"{}";
+        let signature = sign(SECRET, &timestamp, body);
+        assert!(matches!(
+            Webhook::new(SECRET).verify(&signature, &timestamp, body),
+            Err(WebhookError::StaleTimestamp { .. })
+        ));
+        assert!(Webhook::new(SECRET)
+            .tolerance(Duration::from_secs(3600))
+            .verify(&signature, &timestamp, body)
+            .is_ok());
+        assert!(matches!(
+            Webhook::new(SECRET).verify(&signature, "yesterday", body),
+            Err(WebhookError::InvalidTimestamp(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_malformed_payloads() {
+        let timestamp = Utc::now().timestamp().to_string();
+        let body = br#"{"id": "evt_123"}"#;
+        let signature = sign(SECRET, &timestamp, body);
+        assert!(matches!(
+            Webhook::new(SECRET).construct_event(&signature, &timestamp, body),
+            Err(WebhookError::MalformedPayload(_))
+        ));
+    }
+}
@@ -0,0 +1,114 @@
+//! A ready-made axum endpoint for receiving webhooks, enabled by the `axum` feature.
+//!
+//! ```ignore
+//! let router = WebhookRouter::new(Webhook::new(secret))
+//!     .on(EventTypeId::LetterReturnedToSender, handle_returned_letter);
+//! let app = lob::webhook::axum::routes("/webhooks/lob", router);
+//! ```
+//!
+//! Deliveries are answered with `200 OK` once every matching handler succeeded, and with an
+//! error status otherwise: `401` for missing or invalid signatures and stale timestamps, `400`
+//! for payloads that are not events, and `500` when a handler failed, so Lob retries it.
+
+use super::{WebhookError, WebhookHeaders, WebhookRouter};
+use ::axum::body::Bytes;
+use ::axum::extract::State;
+use ::axum::http::{HeaderMap, StatusCode};
+use ::axum::routing::post;
+use ::axum::Router;
+
+impl WebhookHeaders for HeaderMap {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.get(name).and_then(|value| value.to_str().ok())
+    }
+}
+
+/// A router accepting webhook deliveries as `POST` requests to `path`.
+pub fn routes<S>(path: &str, router: WebhookRouter) -> Router<S> {
+    Router::new().route(path, post(receive)).with_state(router)
+}
+
+/// The handler behind `routes`, for mounting with an existing `WebhookRouter` state.
+pub async fn receive(
+    State(router): State<WebhookRouter>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> StatusCode {
+    match router.handle(&headers, &body).await {
+        Ok(dispatch) if dispatch.is_success() => StatusCode::OK,
+        Ok(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        Err(err) => status(&err),
+    }
+}
+
+fn status(err: &WebhookError) -> StatusCode {
+    match err {
+        WebhookError::MissingHeader(_)
+        | WebhookError::InvalidSignature
+        | WebhookError::InvalidTimestamp(_)
+        | WebhookError::StaleTimestamp { .. } => StatusCode::UNAUTHORIZED,
+        WebhookError::MalformedPayload(_) => StatusCode::BAD_REQUEST,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+    use crate::model::{EventTypeId, Resource};
+    use crate::webhook::{sign, HandlerError, Webhook, SIGNATURE_HEADER, TIMESTAMP_HEADER};
+    use ::axum::body::Body;
+    use ::axum::http::Request;
+    use tokio_test::block_on;
+    use tower::ServiceExt;
+
+    const SECRET: &str = "whsec_test";
+
+    fn app() -> Router {
+        let router = WebhookRouter::new(Webhook::new(SECRET))
+            .on_resource(Resource::Letters, |_| async { Ok(()) })
+            .on(EventTypeId::CheckInTransit, |_| async {
+                Err::<(), HandlerError>("ledger unavailable".into())
+            });
+        routes("/webhooks/lob", router)
+    }
+
+    fn request(event_type: &str, resource: &str, secret: &str) -> Request<Body> {
+        let timestamp = chrono::Utc::now().timestamp_millis().to_string();
+        let body = fixtures::event("evt_123", event_type, resource, fixtures::letter("ltr_123"))
+            .to_string();
+        Request::post("/webhooks/lob")
+            .header(SIGNATURE_HEADER, sign(secret, &timestamp, body.as_bytes()))
+            .header(TIMESTAMP_HEADER, timestamp)
+            .body(Body::from(body))
+            .unwrap()
+    }
+
+    fn post(request: Request<Body>) -> StatusCode {
+        block_on(app().oneshot(request)).unwrap().status()
+    }
+
+    #[test]
+    fn accepts_signed_events() {
+        assert_eq!(
+            post(request("letter.created", "letters", SECRET)),
+            StatusCode::OK
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_signatures() {
+        assert_eq!(
+            post(request("letter.created", "letters", "whsec_other")),
+            StatusCode::UNAUTHORIZED
+        );
+    }
+
+    #[test]
+    fn fails_when_a_handler_fails() {
+        assert_eq!(
+            post(request("check.in_transit", "checks", SECRET)),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+    }
+}
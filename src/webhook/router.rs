@@ -0,0 +1,263 @@
+use super::{Webhook, WebhookError, WebhookHeaders};
+use crate::model::{Event, EventTypeId, Resource};
+use futures::future::{self, BoxFuture, FutureExt};
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+
+/// The error a handler fails with.
+pub type HandlerError = Box<dyn std::error::Error + Send + Sync>;
+
+type Handler = Arc<dyn Fn(Event) -> BoxFuture<'static, Result<(), HandlerError>> + Send + Sync>;
+
+/// Which events a handler was registered for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Route {
+    EventType(EventTypeId),
+    Resource(Resource),
+    Any,
+}
+
+impl Route {
+    fn matches(&self, event: &Event) -> bool {
+        match self {
+            Route::EventType(id) => *id == event.event_type.id,
+            Route::Resource(resource) => *resource == event.event_type.resource,
+            Route::Any => true,
+        }
+    }
+}
+
+/// Verifies webhook deliveries and dispatches each event to the handlers registered for it.
+///
+/// All matching handlers run concurrently, so no order of execution is guaranteed; a failing
+/// handler does not prevent the others from running. Failures are reported in the order the
+/// handlers were registered.
+#[derive(Clone)]
+pub struct WebhookRouter {
+    webhook: Webhook,
+    routes: Vec<(Route, Handler)>,
+}
+
+impl WebhookRouter {
+    pub fn new(webhook: Webhook) -> WebhookRouter {
+        WebhookRouter {
+            webhook,
+            routes: Vec::new(),
+        }
+    }
+
+    /// Handle events of a single type, e.g. `EventTypeId::LetterReturnedToSender`.
+    pub fn on<F, Fut>(self, event_type: EventTypeId, handler: F) -> WebhookRouter
+    where
+        F: Fn(Event) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), HandlerError>> + Send + 'static,
+    {
+        self.route(Route::EventType(event_type), handler)
+    }
+
+    /// Handle every event about a kind of resource, e.g. `Resource::Checks`.
+    pub fn on_resource<F, Fut>(self, resource: Resource, handler: F) -> WebhookRouter
+    where
+        F: Fn(Event) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), HandlerError>> + Send + 'static,
+    {
+        self.route(Route::Resource(resource), handler)
+    }
+
+    /// Handle every event.
+    pub fn on_any<F, Fut>(self, handler: F) -> WebhookRouter
+    where
+        F: Fn(Event) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), HandlerError>> + Send + 'static,
+    {
+        self.route(Route::Any, handler)
+    }
+
+    fn route<F, Fut>(mut self, route: Route, handler: F) -> WebhookRouter
+    where
+        F: Fn(Event) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), HandlerError>> + Send + 'static,
+    {
+        self.routes
+            .push((route, Arc::new(move |event| handler(event).boxed())));
+        self
+    }
+
+    /// Verify a delivery from its request headers and raw body, then dispatch its event. This is
+    /// the entry point for receiving webhooks from any HTTP framework.
+    pub async fn handle<H>(&self, headers: &H, body: &[u8]) -> Result<Dispatch, WebhookError>
+    where
+        H: WebhookHeaders + ?Sized,
+    {
+        let event = self.webhook.construct_event_from_headers(headers, body)?;
+        Ok(self.dispatch(event).await)
+    }
+
+    /// Dispatch an already verified event.
+    pub async fn dispatch(&self, event: Event) -> Dispatch {
+        let matching: Vec<_> = self
+            .routes
+            .iter()
+            .filter(|(route, _)| route.matches(&event))
+            .collect();
+        let handled = matching.len();
+        let results =
+            future::join_all(matching.iter().map(|(_, handler)| handler(event.clone()))).await;
+        let failures = matching
+            .into_iter()
+            .zip(results)
+            .filter_map(|((route, _), result)| {
+                result.err().map(|error| HandlerFailure {
+                    route: route.clone(),
+                    error,
+                })
+            })
+            .collect();
+        Dispatch {
+            event,
+            handled,
+            failures,
+        }
+    }
+}
+
+impl fmt::Debug for WebhookRouter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebhookRouter")
+            .field("webhook", &self.webhook)
+            .field(
+                "routes",
+                &self
+                    .routes
+                    .iter()
+                    .map(|(route, _)| route)
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+/// The outcome of dispatching an event.
+#[derive(Debug)]
+pub struct Dispatch {
+    pub event: Event,
+    /// How many handlers matched the event.
+    pub handled: usize,
+    pub failures: Vec<HandlerFailure>,
+}
+
+impl Dispatch {
+    /// Every matching handler succeeded. Respond with an error status otherwise, so Lob retries
+    /// the delivery.
+    pub fn is_success(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+#[derive(Debug)]
+pub struct HandlerFailure {
+    /// The route of the handler that failed.
+    pub route: Route,
+    pub error: HandlerError,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+    use crate::webhook::{sign, SIGNATURE_HEADER, TIMESTAMP_HEADER};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio_test::block_on;
+
+    const SECRET: &str = "whsec_test";
+
+    fn delivery(event_type: &str, resource: &str) -> (Vec<(String, String)>, Vec<u8>) {
+        let timestamp = chrono::Utc::now().timestamp_millis().to_string();
+        let body = fixtures::event("evt_123", event_type, resource, fixtures::letter("ltr_123"))
+            .to_string()
+            .into_bytes();
+        let headers = vec![
+            (
+                SIGNATURE_HEADER.to_lowercase(),
+                sign(SECRET, &timestamp, &body),
+            ),
+            (TIMESTAMP_HEADER.to_owned(), timestamp),
+        ];
+        (headers, body)
+    }
+
+    fn counter(
+        count: &Arc<AtomicUsize>,
+    ) -> impl Fn(Event) -> BoxFuture<'static, Result<(), HandlerError>> {
+        let count = count.clone();
+        move |_| {
+            count.fetch_add(1, Ordering::SeqCst);
+            future::ok(()).boxed()
+        }
+    }
+
+    #[test]
+    fn dispatches_to_matching_handlers() {
+        block_on(async {
+            let (returned, letters, checks, any) = Default::default();
+            let router = WebhookRouter::new(Webhook::new(SECRET))
+                .on(EventTypeId::LetterReturnedToSender, counter(&returned))
+                .on_resource(Resource::Letters, counter(&letters))
+                .on_resource(Resource::Checks, counter(&checks))
+                .on_any(counter(&any));
+
+            let (headers, body) = delivery("letter.returned_to_sender", "letters");
+            let dispatch = router.handle(headers.as_slice(), &body).await.unwrap();
+            assert!(dispatch.is_success());
+            assert_eq!(dispatch.handled, 3);
+            assert_eq!(dispatch.event.as_letter().unwrap().id, "ltr_123");
+
+            let (headers, body) = delivery("letter.created", "letters");
+            router.handle(headers.as_slice(), &body).await.unwrap();
+
+            let counts: [&Arc<AtomicUsize>; 4] = [&returned, &letters, &checks, &any];
+            let counts: Vec<_> = counts.iter().map(|c| c.load(Ordering::SeqCst)).collect();
+            assert_eq!(counts, [1, 2, 0, 2]);
+        })
+    }
+
+    #[test]
+    fn reports_failed_handlers() {
+        block_on(async {
+            let ran = Arc::new(AtomicUsize::new(0));
+            let router = WebhookRouter::new(Webhook::new(SECRET))
+                .on(EventTypeId::LetterCreated, |_| async {
+                    Err::<(), HandlerError>("database unavailable".into())
+                })
+                .on_any(counter(&ran));
+
+            let (headers, body) = delivery("letter.created", "letters");
+            let dispatch = router.handle(headers.as_slice(), &body).await.unwrap();
+            assert!(!dispatch.is_success());
+            assert_eq!(dispatch.failures.len(), 1);
+            assert_eq!(
+                dispatch.failures[0].route,
+                Route::EventType(EventTypeId::LetterCreated)
+            );
+            assert_eq!(
+                dispatch.failures[0].error.to_string(),
+                "database unavailable"
+            );
+            assert_eq!(ran.load(Ordering::SeqCst), 1);
+        })
+    }
+
+    #[test]
+    fn rejects_unsigned_deliveries() {
+        block_on(async {
+            let router = WebhookRouter::new(Webhook::new(SECRET)).on_any(|_| async { Ok(()) });
+            let (mut headers, body) = delivery("letter.created", "letters");
+            headers.remove(0);
+            assert!(matches!(
+                router.handle(headers.as_slice(), &body).await,
+                Err(WebhookError::MissingHeader(SIGNATURE_HEADER))
+            ));
+        })
+    }
+}